@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+use crate::executors::DEFAULT_YEAR;
+
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+const PART_KEYS: [&str; 2] = ["part_one", "part_two"];
+pub const DAY_TABLE_PREFIX: &str = "day_";
+
+/// Escapes the backslashes and quotes in a value, so it can be quoted
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Undoes `escape`, or `None` when a quote or backslash isn't escaped
+fn unescape(quoted: &str) -> Option<String> {
+    let mut value = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                escaped @ ('\\' | '"') => value.push(escaped),
+                _ => return None,
+            },
+            '"' => return None,
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
+
+/// Splits a `<prefix>N` or `YEAR.<prefix>N` table name into its year and day
+fn parse_table_name(name: &str, prefix: &str) -> Option<(Option<u16>, u8)> {
+    let (year, name) = match name.split_once('.') {
+        Some((year, name)) => (Some(year.parse().ok()?), name),
+        None => (None, name),
+    };
+    Some((year, name.strip_prefix(prefix)?.parse().ok()?))
+}
+
+/// Reads a small TOML file of `[<prefix>N]` or `[YEAR.<prefix>N]` tables of
+/// quoted `key = "value"` pairs, handing each pair to `entry` along with its
+/// table's year, if it has one, and day. Errors, including the ones `entry`
+/// returns, say which line they're on.
+pub fn parse_day_tables(
+    contents: &str,
+    prefix: &str,
+    mut entry: impl FnMut(Option<u16>, u8, &str, &str) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut current_table = None;
+    for (i, line) in contents.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let table = parse_table_name(table.trim(), prefix).ok_or_else(|| {
+                format!("line {line_number}: expected a `[{prefix}N]` or `[YEAR.{prefix}N]` table")
+            })?;
+            current_table = Some(table);
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {line_number}: expected `key = \"value\"`"))?;
+        let Some((year, day)) = current_table else {
+            return Err(format!(
+                "line {line_number}: `{}` is outside of a `[{prefix}N]` table",
+                key.trim()
+            ));
+        };
+        let value = value
+            .trim()
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .and_then(unescape)
+            .ok_or_else(|| format!("line {line_number}: values must be quoted strings"))?;
+        entry(year, day, key.trim(), &value).map_err(|e| format!("line {line_number}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Recorded answers for one year, a `part_one` / `part_two` string key per
+/// solved part in a `[day_N]` table per day. The same layout with a different
+/// table prefix holds the answers to a day's examples.
+#[derive(Default, Debug, PartialEq)]
+pub struct Answers {
+    days: BTreeMap<u8, [Option<String>; 2]>,
+}
+
+impl Answers {
+    pub fn load_tables(path: &str, prefix: &str) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Answers::parse_tables(&contents, prefix).map_err(|e| format!("{path}: {e}"))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    fn parse_tables(contents: &str, prefix: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        parse_day_tables(contents, prefix, |year, day, key, value| match year {
+            Some(_) => Err(format!("`[{prefix}N]` tables don't have a year")),
+            None => answers.record_key(day, key, value),
+        })?;
+        Ok(answers)
+    }
+
+    fn record_key(&mut self, day: u8, key: &str, answer: &str) -> Result<(), String> {
+        let part = PART_KEYS
+            .iter()
+            .position(|&k| k == key)
+            .ok_or_else(|| format!("unknown key `{key}`"))?;
+        self.record(day, part as u8 + 1, answer);
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.days.get(&day)?[part as usize - 1].as_deref()
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: &str) {
+        self.days.entry(day).or_default()[part as usize - 1] = Some(answer.to_owned());
+    }
+
+    pub fn save_tables(&self, path: &str, prefix: &str) -> io::Result<()> {
+        fs::write(path, self.to_toml(prefix))
+    }
+
+    fn to_toml(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        for (day, parts) in &self.days {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            _ = writeln!(out, "[{prefix}{day}]");
+            for (key, answer) in PART_KEYS.iter().zip(parts) {
+                if let Some(answer) = answer {
+                    _ = writeln!(out, "{key} = \"{}\"", escape(answer));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// The answers file, with every year's answers in `[YEAR.day_N]` tables.
+/// Tables without a year are from before there was more than one, and hold
+/// the default year's answers.
+#[derive(Default, Debug, PartialEq)]
+pub struct RecordedAnswers {
+    years: BTreeMap<u16, Answers>,
+}
+
+impl RecordedAnswers {
+    pub fn load(path: &str) -> Result<RecordedAnswers, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => RecordedAnswers::parse(&contents).map_err(|e| format!("{path}: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(RecordedAnswers::default()),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<RecordedAnswers, String> {
+        let mut recorded = RecordedAnswers::default();
+        parse_day_tables(contents, DAY_TABLE_PREFIX, |year, day, key, value| {
+            recorded
+                .year_mut(year.unwrap_or(DEFAULT_YEAR))
+                .record_key(day, key, value)
+        })?;
+        Ok(recorded)
+    }
+
+    pub fn year(&self, year: u16) -> Option<&Answers> {
+        self.years.get(&year)
+    }
+
+    pub fn year_mut(&mut self, year: u16) -> &mut Answers {
+        self.years.entry(year).or_default()
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    fn to_toml(&self) -> String {
+        let tables: Vec<_> = self
+            .years
+            .iter()
+            .filter(|(_, answers)| !answers.days.is_empty())
+            .map(|(year, answers)| answers.to_toml(&format!("{year}.{DAY_TABLE_PREFIX}")))
+            .collect();
+        tables.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut recorded = RecordedAnswers::default();
+        recorded.year_mut(2023).record(10, 1, "6842");
+        recorded.year_mut(2023).record(2, 1, "8");
+        recorded.year_mut(2024).record(2, 2, "2286");
+
+        let serialized = recorded.to_toml();
+        assert_eq!(
+            serialized,
+            "[2023.day_2]\npart_one = \"8\"\n\n[2023.day_10]\npart_one = \"6842\"\n\n[2024.day_2]\npart_two = \"2286\"\n"
+        );
+        assert_eq!(RecordedAnswers::parse(&serialized), Ok(recorded));
+    }
+
+    #[test]
+    fn quotes_and_backslashes_round_trip() {
+        let mut recorded = RecordedAnswers::default();
+        recorded.year_mut(2023).record(1, 1, r#"say "hi" \o/"#);
+        let serialized = recorded.to_toml();
+        assert_eq!(
+            serialized,
+            "[2023.day_1]\npart_one = \"say \\\"hi\\\" \\\\o/\"\n"
+        );
+        assert_eq!(RecordedAnswers::parse(&serialized), Ok(recorded));
+        assert!(RecordedAnswers::parse("[day_1]\npart_one = \"a\"b\"\n").is_err());
+        assert!(RecordedAnswers::parse("[day_1]\npart_one = \"a\\\"\n").is_err());
+    }
+
+    #[test]
+    fn tables_without_a_year_are_the_default_years() {
+        let recorded = RecordedAnswers::parse("[day_1]\npart_one = \"142\"\n").unwrap();
+        assert_eq!(recorded.year(DEFAULT_YEAR).unwrap().get(1, 1), Some("142"));
+    }
+
+    #[test]
+    fn parse_reports_line_numbers() {
+        let err = RecordedAnswers::parse("[day_1]\npart_one = 142\n").unwrap_err();
+        assert_eq!(err, "line 2: values must be quoted strings");
+        let err = RecordedAnswers::parse("[day_1]\npart_three = \"7\"\n").unwrap_err();
+        assert_eq!(err, "line 2: unknown key `part_three`");
+        let err = RecordedAnswers::parse("[twenty.day_1]\n").unwrap_err();
+        assert_eq!(err, "line 1: expected a `[day_N]` or `[YEAR.day_N]` table");
+    }
+}
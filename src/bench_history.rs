@@ -0,0 +1,307 @@
+//! Saved benchmark results, so a run can be compared with the code as it was
+//! at an earlier commit, and the time budgets days are expected to stay in.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::answers;
+use crate::executors::DEFAULT_YEAR;
+
+pub const HISTORY_PATH: &str = "bench_history.csv";
+pub const BUDGETS_PATH: &str = "bench_budgets.toml";
+
+const HISTORY_HEADER: &str = "timestamp,commit,input_hash,day,threads,phase,median_ns";
+
+/// The phases that are timed, as they're named in the history and budgets
+pub const PHASES: [&str; 4] = ["parse", "part_one", "part_two", "total"];
+
+/// Differences smaller than this are timer noise, whatever the percentage
+const NOISE_FLOOR: Duration = Duration::from_micros(10);
+
+/// The median time of one phase of one day, measured at one commit
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub timestamp: u64,
+    pub commit: String,
+    pub input_hash: String,
+    pub day: u8,
+    pub threads: usize,
+    pub phase: String,
+    pub median: Duration,
+}
+
+impl Entry {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.timestamp,
+            self.commit,
+            self.input_hash,
+            self.day,
+            self.threads,
+            self.phase,
+            self.median.as_nanos()
+        )
+    }
+
+    fn from_csv(line: &str) -> Option<Entry> {
+        let fields: Vec<&str> = line.split(',').collect();
+        let [timestamp, commit, input_hash, day, threads, phase, median_ns] = fields[..] else {
+            return None;
+        };
+        Some(Entry {
+            timestamp: timestamp.parse().ok()?,
+            commit: commit.to_owned(),
+            input_hash: input_hash.to_owned(),
+            day: day.parse().ok()?,
+            threads: threads.parse().ok()?,
+            phase: phase.to_owned(),
+            median: Duration::from_nanos(median_ns.parse().ok()?),
+        })
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// The commit the working tree is at, marked `-dirty` when tracked files have
+/// changed since, or `unknown` outside of a git checkout
+pub fn current_commit() -> String {
+    let Some(commit) = git(&["rev-parse", "HEAD"]) else {
+        return "unknown".to_owned();
+    };
+    match git(&["status", "--porcelain", "--untracked-files=no"]) {
+        Some(changes) if changes.is_empty() => commit,
+        _ => format!("{commit}-dirty"),
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Adds entries to the end of the history, starting the file if need be
+pub fn append(path: &str, entries: &[Entry]) -> Result<(), String> {
+    let is_new = fs::metadata(path).is_err();
+    let mut out = String::new();
+    if is_new {
+        _ = writeln!(out, "{HISTORY_HEADER}");
+    }
+    for entry in entries {
+        _ = writeln!(out, "{}", entry.to_csv());
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(out.as_bytes()))
+        .map_err(|e| format!("Unable to save to {path}: {e}"))
+}
+
+pub fn load(path: &str) -> Result<Vec<Entry>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(format!(
+                "There is no benchmark history in {path} yet, save some with `bench --save`"
+            ))
+        }
+        Err(e) => return Err(format!("{path}: {e}")),
+    };
+    contents
+        .lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            Entry::from_csv(line.trim())
+                .ok_or_else(|| format!("{path}: line {} is not a benchmark result", i + 1))
+        })
+        .collect()
+}
+
+/// Whether `saved` is the commit a baseline names. A baseline git knows is
+/// compared in full, so a run at `<hash>-dirty` isn't taken for one at
+/// `<hash>`, while anything else can be a saved commit written out or the
+/// start of a clean one's hash.
+fn is_baseline(saved: &str, baseline: &str, resolved: Option<&str>) -> bool {
+    match resolved {
+        Some(commit) => saved == commit,
+        None if baseline.chars().all(|c| c.is_ascii_hexdigit()) => {
+            saved.starts_with(baseline) && saved.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => saved == baseline,
+    }
+}
+
+/// The latest saved entry for each day, thread count and phase measured at
+/// `baseline`, which can be anything git can name a commit by, or a saved
+/// commit as it's written in the history
+pub fn baseline_entries(history: &[Entry], baseline: &str) -> BTreeMap<(u8, usize, String), Entry> {
+    let resolved = git(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{baseline}^{{commit}}"),
+    ]);
+    let mut latest = BTreeMap::new();
+    for entry in history
+        .iter()
+        .filter(|e| is_baseline(&e.commit, baseline, resolved.as_deref()))
+    {
+        let key = (entry.day, entry.threads, entry.phase.clone());
+        latest.insert(key, entry.clone());
+    }
+    latest
+}
+
+/// How much slower `current` is than `baseline`, as a percentage, or `None`
+/// when the difference is too small to mean anything
+pub fn slowdown(current: Duration, baseline: Duration) -> Option<f64> {
+    if current.abs_diff(baseline) < NOISE_FLOOR || baseline.is_zero() {
+        return None;
+    }
+    Some((current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0)
+}
+
+/// Reads a duration written the way `Duration`'s debug output writes them,
+/// such as `250µs`, `1.5ms` or `2s`
+fn parse_duration(text: &str) -> Option<Duration> {
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let (value, unit) = text.split_at(split);
+    let value: f64 = value.parse().ok()?;
+    let seconds_per_unit = match unit.trim() {
+        "ns" => 1e-9,
+        "µs" | "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(value * seconds_per_unit).ok()
+}
+
+/// The longest each phase of a day may take on the default thread pool, the
+/// one `execute` runs on, stored as a small TOML file with one `[day_N]` table
+/// per day and a quoted duration for any of the phases. Days of a year other
+/// than the default one go in `[YEAR.day_N]` tables.
+///
+/// ```toml
+/// [day_12]
+/// part_two = "20ms"
+/// total = "25ms"
+/// ```
+#[derive(Default, Debug, PartialEq)]
+pub struct Budgets {
+    days: BTreeMap<(u16, u8), [Option<Duration>; 4]>,
+}
+
+impl Budgets {
+    /// Loads the budgets, or none when the file doesn't exist
+    pub fn load(path: &str) -> Result<Budgets, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Budgets::parse(&contents).map_err(|e| format!("{path}: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Budgets::default()),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Budgets, String> {
+        let mut budgets = Budgets::default();
+        answers::parse_day_tables(
+            contents,
+            answers::DAY_TABLE_PREFIX,
+            |year, day, key, value| {
+                let phase = PHASES
+                    .iter()
+                    .position(|&p| p == key)
+                    .ok_or_else(|| format!("unknown phase `{key}`"))?;
+                let budget =
+                    parse_duration(value).ok_or("budgets must be durations such as \"5ms\"")?;
+                let year = year.unwrap_or(DEFAULT_YEAR);
+                budgets.days.entry((year, day)).or_default()[phase] = Some(budget);
+                Ok(())
+            },
+        )?;
+        Ok(budgets)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    pub fn get(&self, year: u16, day: u8, phase: &str) -> Option<Duration> {
+        let phase = PHASES.iter().position(|&p| p == phase)?;
+        self.days.get(&(year, day))?[phase]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip() {
+        let entry = Entry {
+            timestamp: 1_702_000_000,
+            commit: "0123abcd-dirty".to_owned(),
+            input_hash: format!("{:016x}", 42),
+            day: 12,
+            threads: 4,
+            phase: "part_two".to_owned(),
+            median: Duration::from_micros(1500),
+        };
+        assert_eq!(Entry::from_csv(&entry.to_csv()), Some(entry));
+    }
+
+    #[test]
+    fn budgets_parse() {
+        let budgets = Budgets::parse(
+            "# Keep the hot loops honest\n[day_12]\npart_two = \"1.5ms\"\ntotal = \"250µs\"\n\n[2024.day_16]\nparse = \"2s\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            budgets.get(2023, 12, "part_two"),
+            Some(Duration::from_micros(1500))
+        );
+        assert_eq!(
+            budgets.get(2023, 12, "total"),
+            Some(Duration::from_micros(250))
+        );
+        assert_eq!(budgets.get(2023, 12, "parse"), None);
+        assert_eq!(budgets.get(2024, 16, "parse"), Some(Duration::from_secs(2)));
+        assert_eq!(budgets.get(2023, 16, "parse"), None);
+        assert!(Budgets::parse("[day_1]\npart_one = \"fast\"\n").is_err());
+        assert!(Budgets::parse("[day_1]\npart_three = \"1ms\"\n").is_err());
+    }
+
+    #[test]
+    fn dirty_runs_are_not_the_baseline() {
+        let full = "0123abcd0123abcd0123abcd0123abcd0123abcd";
+        let dirty = format!("{full}-dirty");
+        assert!(is_baseline(full, "HEAD", Some(full)));
+        assert!(!is_baseline(&dirty, "HEAD", Some(full)));
+        assert!(is_baseline(full, "0123ab", None));
+        assert!(!is_baseline(&dirty, "0123ab", None));
+        assert!(is_baseline(&dirty, &dirty, None));
+        assert!(!is_baseline(full, "unknown", None));
+    }
+
+    #[test]
+    fn small_differences_are_noise() {
+        let micros = Duration::from_micros;
+        assert_eq!(slowdown(micros(105), micros(100)), None);
+        assert!((slowdown(micros(150), micros(100)).unwrap() - 50.0).abs() < 1e-9);
+        assert!(slowdown(micros(80), micros(100)).unwrap() < -19.0);
+    }
+}
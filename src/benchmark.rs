@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::bench_history::{self, Budgets, Entry, PHASES};
+use crate::execution_driver::{
+    input_hash, normalize_input, read_input, run_day, thread_pool, Parts,
+};
+use crate::executors::{self, Answer, ParseError};
+
+pub struct BenchOptions {
+    pub warmup_iterations: u32,
+    pub iterations: u32,
+    /// Every day is measured on a pool of each of these sizes, and speedups
+    /// are relative to the first
+    pub thread_counts: Vec<usize>,
+    /// Measure this file instead of the day's puzzle input
+    pub input: Option<PathBuf>,
+    /// Append the medians to the benchmark history
+    pub save: bool,
+    /// Compare the medians with the ones saved at this commit
+    pub baseline: Option<String>,
+    /// How many percent slower than the baseline a phase can get before it
+    /// counts as a regression
+    pub threshold: f64,
+    /// The file with the time budgets days are held to on the default pool
+    pub budgets_path: String,
+}
+
+/// One thread, then one per core
+pub fn default_thread_counts() -> Vec<usize> {
+    let cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if cores == 1 {
+        vec![1]
+    } else {
+        vec![1, cores]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        // Nearest-rank percentile, so p95 is always an observed sample
+        let percentile = |p: usize| sorted[((p * n).div_ceil(100)).clamp(1, n) - 1];
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_nanos() as f64 - mean.as_nanos() as f64).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean,
+            p95: percentile(95),
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PhaseSamples {
+    parse: Vec<Duration>,
+    part_one: Vec<Duration>,
+    part_two: Vec<Duration>,
+    total: Vec<Duration>,
+}
+
+/// The answers to both parts, which have to come out the same on every run
+/// whatever the thread count
+type Answers = (Option<Answer>, Option<Answer>);
+
+enum BenchFailure {
+    Parse(ParseError),
+    /// Runs on the same pool gave different answers
+    AnswersChanged,
+}
+
+fn bench_day(
+    year: u16,
+    day: u8,
+    input: &str,
+    options: &BenchOptions,
+) -> Result<(PhaseSamples, Answers), BenchFailure> {
+    let mut samples = PhaseSamples::default();
+    let mut answers = None;
+    for iteration in 0..options.warmup_iterations + options.iterations {
+        // Every iteration starts from a freshly parsed executor so parsing is
+        // measured each time
+        let executor = executors::get_executor(year, day).expect("checked by bench");
+        let result = run_day(executor, input, Parts::Both).map_err(BenchFailure::Parse)?;
+        let run_answers = (result.p1_answer.clone(), result.p2_answer.clone());
+        match &answers {
+            None => answers = Some(run_answers),
+            Some(first) if *first != run_answers => return Err(BenchFailure::AnswersChanged),
+            Some(_) => {}
+        }
+        if iteration < options.warmup_iterations {
+            continue;
+        }
+        samples.parse.push(result.parse_time);
+        samples.part_one.push(result.p1_time);
+        samples.part_two.push(result.p2_time);
+        samples.total.push(result.total_time());
+    }
+    Ok((
+        samples,
+        answers.expect("at least one iteration is measured"),
+    ))
+}
+
+fn describe_answers((part_one, part_two): &Answers) -> String {
+    let describe =
+        |answer: &Option<Answer>| answer.as_ref().map_or("-".to_owned(), Answer::to_string);
+    format!("{} / {}", describe(part_one), describe(part_two))
+}
+
+/// Prints how the medians compare with the ones saved at the baseline,
+/// returning whether none of them regressed
+fn compare(
+    entries: &[Entry],
+    baseline: &str,
+    saved: &BTreeMap<(u8, usize, String), Entry>,
+    threshold: f64,
+) -> bool {
+    println!("\nCompared with {baseline}, flagging changes over {threshold}%:");
+    let mut regressions = 0;
+    let mut unsaved = 0;
+    let mut input_changed = BTreeSet::new();
+    for entry in entries {
+        let key = (entry.day, entry.threads, entry.phase.clone());
+        let Some(before) = saved.get(&key) else {
+            unsaved += 1;
+            continue;
+        };
+        if before.input_hash != entry.input_hash {
+            input_changed.insert(entry.day);
+            continue;
+        }
+        let Some(slowdown) = bench_history::slowdown(entry.median, before.median) else {
+            continue;
+        };
+        let verdict = if slowdown > threshold {
+            regressions += 1;
+            "REGRESSION"
+        } else if slowdown < -threshold {
+            "faster"
+        } else {
+            continue;
+        };
+        println!(
+            "{:>3} | {:>7} | {:<8} | {:>12} -> {:>12} | {slowdown:+7.1}% | {verdict}",
+            entry.day,
+            entry.threads,
+            entry.phase.replace('_', " "),
+            format!("{:?}", before.median),
+            format!("{:?}", entry.median),
+        );
+    }
+    for day in &input_changed {
+        println!("{day:>3} | not compared, the input differs from the one measured at {baseline}");
+    }
+    if unsaved > 0 {
+        println!("{unsaved} measurement(s) have nothing saved at {baseline} to compare with");
+    }
+    match regressions {
+        0 => println!("Nothing is more than {threshold}% slower"),
+        n => println!("{n} phase(s) are more than {threshold}% slower"),
+    }
+    regressions == 0
+}
+
+/// Benchmarks each day on every thread count, returning whether every day
+/// gave the same answers throughout, stayed within its budgets and didn't
+/// get slower than the baseline
+pub fn bench(year: u16, days: RangeInclusive<u8>, options: BenchOptions) -> Result<bool, String> {
+    let budgets = Budgets::load(&options.budgets_path)?;
+    let saved = match &options.baseline {
+        Some(baseline) => {
+            let history = bench_history::load(bench_history::HISTORY_PATH)?;
+            let saved = bench_history::baseline_entries(&history, baseline);
+            if saved.is_empty() {
+                return Err(format!(
+                    "Nothing is saved in {} for {baseline}",
+                    bench_history::HISTORY_PATH
+                ));
+            }
+            Some(saved)
+        }
+        None => None,
+    };
+    let commit = bench_history::current_commit();
+    let timestamp = bench_history::now();
+    let mut entries = vec![];
+    let mut over_budget = vec![];
+    // Budgets are for the pool `execute` runs on by default, the other thread
+    // counts are only there to compare against
+    let budget_threads = rayon::current_num_threads();
+    if !budgets.is_empty() && !options.thread_counts.contains(&budget_threads) {
+        println!(
+            "Budgets in {} are for {budget_threads} thread(s), which isn't measured, so they aren't checked",
+            options.budgets_path
+        );
+    }
+
+    let pools = options
+        .thread_counts
+        .iter()
+        .map(|&threads| Ok((threads, thread_pool(Some(threads))?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let thread_counts: Vec<_> = options.thread_counts.iter().map(usize::to_string).collect();
+    println!(
+        "{} warm-up and {} measured iterations per day on {} thread(s)",
+        options.warmup_iterations,
+        options.iterations,
+        thread_counts.join(", ")
+    );
+    println!(
+        "{:>3} | {:>7} | {:<8} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+        "Day", "Threads", "Phase", "Min", "Median", "Mean", "p95", "Std dev"
+    );
+    println!("{}", "-".repeat(106));
+
+    let mut consistent = true;
+    for day in days {
+        match executors::get_executor(year, day) {
+            None => {
+                println!("{day:>3} | skipped (not registered)");
+                continue;
+            }
+            Some(executor) if !executor.is_implemented() => {
+                println!("{day:>3} | skipped (not implemented)");
+                continue;
+            }
+            Some(_) => {}
+        }
+        let input = match &options.input {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|e| format!("Unable to read {}: {e}", path.display()))?,
+            None => match read_input(year, day) {
+                Ok(input) => input,
+                Err(_) => {
+                    println!("{day:>3} | skipped (no input)");
+                    continue;
+                }
+            },
+        };
+        let input = normalize_input(&input);
+        let hash = format!("{:016x}", input_hash(&input));
+
+        // The first pool's answers and median total are what the others are
+        // compared against
+        let mut baseline: Option<(usize, Answers, Duration)> = None;
+        let mut speedups = vec![];
+        for (threads, pool) in &pools {
+            let (samples, answers) = match pool.install(|| bench_day(year, day, &input, &options)) {
+                Ok(measured) => measured,
+                Err(BenchFailure::Parse(error)) => {
+                    println!("{day:>3} | skipped (parse error, {error})");
+                    break;
+                }
+                Err(BenchFailure::AnswersChanged) => {
+                    println!("{day:>3} | {threads:>7} | FAIL: answers changed between runs");
+                    consistent = false;
+                    break;
+                }
+            };
+            let phase_samples = [
+                &samples.parse,
+                &samples.part_one,
+                &samples.part_two,
+                &samples.total,
+            ];
+            for (phase, phase_samples) in PHASES.into_iter().zip(phase_samples) {
+                let stats = Stats::from_samples(phase_samples);
+                println!(
+                    "{:>3} | {:>7} | {:<8} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+                    day,
+                    threads,
+                    phase.replace('_', " "),
+                    format!("{:?}", stats.min),
+                    format!("{:?}", stats.median),
+                    format!("{:?}", stats.mean),
+                    format!("{:?}", stats.p95),
+                    format!("{:?}", stats.stddev),
+                );
+                let budget = budgets
+                    .get(year, day, phase)
+                    .filter(|_| *threads == budget_threads);
+                if let Some(budget) = budget.filter(|&b| stats.median > b) {
+                    over_budget.push(format!(
+                        "{day:>3} | {threads:>7} | {:<8} | median {:?} is over the {budget:?} budget",
+                        phase.replace('_', " "),
+                        stats.median,
+                    ));
+                }
+                entries.push(Entry {
+                    timestamp,
+                    commit: commit.clone(),
+                    input_hash: hash.clone(),
+                    day,
+                    threads: *threads,
+                    phase: phase.to_owned(),
+                    median: stats.median,
+                });
+            }
+
+            let median_total = Stats::from_samples(&samples.total).median;
+            match &baseline {
+                None => baseline = Some((*threads, answers, median_total)),
+                Some((base_threads, base_answers, _)) if *base_answers != answers => {
+                    println!(
+                        "{day:>3} | {threads:>7} | FAIL: answers {} differ from {} on {base_threads} thread(s)",
+                        describe_answers(&answers),
+                        describe_answers(base_answers),
+                    );
+                    consistent = false;
+                }
+                Some((_, _, base_total)) => {
+                    let speedup = base_total.as_secs_f64() / median_total.as_secs_f64();
+                    speedups.push(format!("{threads} threads {speedup:.2}x"));
+                }
+            }
+        }
+        if let (Some((base_threads, ..)), false) = (&baseline, speedups.is_empty()) {
+            println!(
+                "{day:>3} | speedup | median total against {base_threads} thread(s): {}",
+                speedups.join(", ")
+            );
+        }
+    }
+
+    let mut passed = consistent;
+    if !over_budget.is_empty() {
+        println!("\nOver budget in {}:", options.budgets_path);
+        for line in &over_budget {
+            println!("{line}");
+        }
+        passed = false;
+    }
+    if let (Some(baseline), Some(saved)) = (&options.baseline, &saved) {
+        passed &= compare(&entries, baseline, saved, options.threshold);
+    }
+    if options.save {
+        bench_history::append(bench_history::HISTORY_PATH, &entries)?;
+        println!(
+            "\nSaved {} result(s) for {commit} to {}",
+            entries.len(),
+            bench_history::HISTORY_PATH
+        );
+    }
+    Ok(passed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn stats_odd_sample_count() {
+        let stats = Stats::from_samples(&millis(&[5, 1, 3]));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn stats_even_sample_count() {
+        let stats = Stats::from_samples(&millis(&[4, 1, 3, 2]));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn stats_p95_nearest_rank() {
+        let samples = millis(&(1..=20).collect::<Vec<_>>());
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn stats_constant_samples_have_no_deviation() {
+        let stats = Stats::from_samples(&millis(&[7, 7, 7, 7]));
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert_eq!(stats.mean, Duration::from_millis(7));
+    }
+}
@@ -1,27 +1,542 @@
 use std::fs;
-use std::time::Instant;
+use std::io::{self, Read};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use crate::executors::Executor;
+use crate::alloc_metrics::{self, AllocStats};
+use crate::executors::{self, Answer, Executor, InputKind, ParseError, DEFAULT_YEAR};
+use crate::puzzle_page;
+use crate::report::{self, OutputFormat};
 
-pub fn execute(mut executors: Vec<Box<dyn Executor>>, day: u8) {
-    let executor = &mut executors[day as usize - 1];
-    let input = fs::read_to_string(format!("inputs/day_{day}"))
-        .expect("Encountered an error reading input file");
-    let _global_pool = rayon::ThreadPoolBuilder::new().build_global();
-    let mut p1_buffer = String::with_capacity(1024);
-    let mut p2_buffer = String::with_capacity(1024);
+pub struct DayResult {
+    pub parse_time: Duration,
+    pub p1_time: Duration,
+    pub p2_time: Duration,
+    /// `None` for a part that wasn't asked for
+    pub p1_answer: Option<Answer>,
+    pub p2_answer: Option<Answer>,
+    /// Allocations during parsing, part one, part two and the whole run,
+    /// when the build counts them
+    pub allocations: Option<[AllocStats; 4]>,
+}
+
+impl DayResult {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.p1_time + self.p2_time
+    }
+}
+
+/// Which parts of a day to run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parts {
+    #[default]
+    Both,
+    One,
+    Two,
+}
+
+impl Parts {
+    /// Parses the value of `--part`, where no value means both parts
+    pub fn from_arg(part: Option<u8>) -> Parts {
+        match part {
+            None => Parts::Both,
+            Some(1) => Parts::One,
+            Some(_) => Parts::Two,
+        }
+    }
+
+    pub fn includes(self, part: u8) -> bool {
+        matches!(
+            (self, part),
+            (Parts::Both, _) | (Parts::One, 1) | (Parts::Two, 2)
+        )
+    }
+}
+
+/// Where an executor's input comes from
+#[derive(Clone, Debug, Default)]
+pub enum InputSource {
+    #[default]
+    Puzzle,
+    /// One of the day's examples, numbered from 1
+    Example(usize),
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Parses the value of `--input`, where `-` means stdin
+    pub fn from_arg(arg: &str) -> InputSource {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+}
+
+pub enum DayOutcome {
+    Completed { result: DayResult, input_hash: u64 },
+    ParseFailed(ParseError),
+    Skipped(&'static str),
+}
+
+/// FNV-1a, so the hash of an input is stable across runs, platforms and
+/// toolchains
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub const INPUTS_DIR: &str = "inputs";
+
+/// Inputs are namespaced by year, as in `inputs/2023/day_1`
+pub fn inputs_dir(year: u16) -> PathBuf {
+    Path::new(INPUTS_DIR).join(year.to_string())
+}
+
+pub fn input_path(year: u16, day: u8) -> PathBuf {
+    inputs_dir(year).join(format!("day_{day}"))
+}
+
+/// Reads a day's puzzle input. Inputs for the default year that were
+/// downloaded before inputs were namespaced are still picked up from
+/// `inputs/day_N`.
+pub fn read_input(year: u16, day: u8) -> io::Result<String> {
+    match fs::read_to_string(input_path(year, day)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound && year == DEFAULT_YEAR => {
+            fs::read_to_string(Path::new(INPUTS_DIR).join(format!("day_{day}")))
+        }
+        result => result,
+    }
+}
+
+/// Parses the input and runs the parts. The executor is taken by value since
+/// it may borrow from the input, which only has to outlive this call.
+pub fn run_day<'a>(
+    mut executor: Box<dyn Executor<'a> + 'a>,
+    input: &'a str,
+    parts: Parts,
+) -> Result<DayResult, ParseError> {
+    let run = alloc_metrics::Phase::start();
+    let phase = alloc_metrics::Phase::start();
     let start = Instant::now();
-    executor.parse(input);
+    executor.parse(input)?;
     let parse_time = start.elapsed();
-    executor.part_one(&mut p1_buffer);
+    let parse_allocations = phase.finish();
+
+    let phase = alloc_metrics::Phase::start();
+    let p1_answer = parts.includes(1).then(|| executor.part_one());
     let p1_time = start.elapsed();
-    executor.part_two(&mut p2_buffer);
+    let p1_allocations = phase.finish();
+
+    let phase = alloc_metrics::Phase::start();
+    let p2_answer = parts.includes(2).then(|| executor.part_two());
     let p2_time = start.elapsed();
+    let p2_allocations = phase.finish();
+    let run_allocations = run.finish();
+
+    Ok(DayResult {
+        parse_time,
+        p1_time: p1_time - parse_time,
+        p2_time: p2_time - p1_time,
+        p1_answer,
+        p2_answer,
+        allocations: parse_allocations
+            .zip(p1_allocations)
+            .zip(p2_allocations)
+            .zip(run_allocations)
+            .map(|(((parse, p1), p2), total)| [parse, p1, p2, total]),
+    })
+}
+
+/// Builds the pool that executors' rayon code runs on when called inside
+/// `install`. Without a thread count rayon picks one, normally one per core.
+pub fn thread_pool(threads: Option<usize>) -> Result<rayon::ThreadPool, String> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
+        .build()
+        .map_err(|e| format!("Unable to start a thread pool: {e}"))
+}
+
+/// Irons out how an input was saved, so executors only ever see `\n` line
+/// endings, no byte order mark, no trailing whitespace on any line and a
+/// single newline at the end
+pub fn normalize_input(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    let content_length = normalized.trim_end_matches('\n').len();
+    normalized.truncate(content_length);
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Describes a parse error along with the line of input it points at, e.g.
+///
+/// ```text
+/// line 2, column 17: unexpected '\r'
+///     2 | AAA = (BBB, CCC)\r
+///       |                 ^
+/// ```
+pub fn describe_parse_error(input: &str, error: &ParseError) -> String {
+    let Some(line) = input.split('\n').nth(error.line - 1) else {
+        return error.to_string();
+    };
+    // Control characters are escaped so a stray `\r` shows up, which means
+    // the caret has to account for the escapes before it
+    let caret_offset: usize = line
+        .chars()
+        .take(error.column - 1)
+        .map(|c| c.escape_debug().count())
+        .sum();
+    let number = error.line.to_string();
+    let mut description = format!(
+        "{error}\n{:>4} | {}\n{:>4} | {}^",
+        number,
+        line.escape_debug(),
+        "",
+        " ".repeat(caret_offset),
+    );
+    for context in &error.context {
+        description.push_str(&format!(
+            "\n  in the {} starting at line {}, column {}",
+            context.label, context.line, context.column
+        ));
+    }
+    description
+}
+
+/// Reads the input for `day` from `source`, along with which kind of input it
+/// is for the executor that parses it. The executor is built once the input
+/// has been read, since it may borrow from it.
+pub fn load_input(year: u16, day: u8, source: &InputSource) -> Result<(String, InputKind), String> {
+    let examples = executors::get_executor(year, day)
+        .ok_or_else(|| format!("No executor is registered for {year} day {day}"))?
+        .examples();
+    let kind = match source {
+        InputSource::Example(_) => InputKind::Example,
+        _ => InputKind::Puzzle,
+    };
+    let input = match source {
+        InputSource::Puzzle => read_input(year, day).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!(
+                "No input found at {}. Run `aoc_2023 fetch {day} --year {year}` to download it.",
+                input_path(year, day).display()
+            ),
+            _ => format!("Unable to read {}: {e}", input_path(year, day).display()),
+        }),
+        InputSource::File(path) => {
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))
+        }
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("Unable to read input from stdin: {e}"))?;
+            Ok(input)
+        }
+        InputSource::Example(n) if examples.is_empty() => {
+            // Days without declared examples fall back to the ones saved by
+            // `fetch --examples`
+            let input =
+                puzzle_page::load_example_input(&inputs_dir(year), day, *n).ok_or_else(|| {
+                    format!(
+                        "Day {day} has no example {n}. Run `aoc_2023 fetch {day} --year {year} \
+                         --examples` to save the examples from the puzzle page."
+                    )
+                })?;
+            Ok(input)
+        }
+        InputSource::Example(n) => {
+            let example = n
+                .checked_sub(1)
+                .and_then(|i| examples.get(i))
+                .ok_or_else(|| {
+                    format!(
+                        "Day {day} has {} example(s), so there is no example {n}",
+                        examples.len()
+                    )
+                })?;
+            Ok(example.input.to_owned())
+        }
+    }?;
+    Ok((normalize_input(&input), kind))
+}
+
+/// The answers the puzzle description gives for example `n` of a day, from
+/// the day's declared examples or else from the ones saved by
+/// `fetch --examples`
+fn example_answers(year: u16, day: u8, n: usize) -> Result<[Option<String>; 2], String> {
+    let examples = executors::get_executor(year, day)
+        .map(|executor| executor.examples())
+        .unwrap_or_default();
+    if let Some(example) = n.checked_sub(1).and_then(|i| examples.get(i)) {
+        return Ok([example.part_one, example.part_two].map(|a| a.map(str::to_owned)));
+    }
+    let saved = puzzle_page::load_example_answers(&inputs_dir(year), day)?;
+    let n = u8::try_from(n).unwrap_or(u8::MAX);
+    Ok([1, 2].map(|part| saved.get(n, part).map(str::to_owned)))
+}
+
+/// Whether a part's answer is the example's, or `None` when there's nothing
+/// to compare because the part wasn't run, isn't written or has no answer
+/// given for the example
+fn example_check(answer: Option<&Answer>, expected: Option<&str>) -> Option<bool> {
+    match (answer, expected) {
+        (Some(Answer::NotImplemented) | None, _) | (_, None) => None,
+        (Some(answer), Some(expected)) => Some(answer.matches(expected)),
+    }
+}
+
+fn complete_day<'a>(
+    executor: Box<dyn Executor<'a> + 'a>,
+    input: &'a str,
+    parts: Parts,
+) -> DayOutcome {
+    let input_hash = input_hash(input);
+    match run_day(executor, input, parts) {
+        Ok(result) => DayOutcome::Completed { result, input_hash },
+        Err(error) => DayOutcome::ParseFailed(error),
+    }
+}
+
+pub fn execute(
+    year: u16,
+    day: u8,
+    source: &InputSource,
+    parts: Parts,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let (input, kind) = load_input(year, day, source)?;
+    let expected = match source {
+        InputSource::Example(n) => example_answers(year, day, *n)?,
+        _ => [None, None],
+    };
+    let mut executor = executors::get_executor(year, day).expect("checked by load_input");
+    executor.set_input_kind(kind);
+    let outcome = complete_day(executor, &input, parts);
+    let wrong_example = match (&outcome, source) {
+        (DayOutcome::Completed { result, .. }, InputSource::Example(n)) => {
+            let answers = [&result.p1_answer, &result.p2_answer];
+            let wrong = answers
+                .into_iter()
+                .zip(&expected)
+                .any(|(answer, expected)| {
+                    example_check(answer.as_ref(), expected.as_deref()) == Some(false)
+                });
+            wrong.then_some(*n)
+        }
+        _ => None,
+    };
+
+    match (format, &outcome) {
+        (_, DayOutcome::ParseFailed(error)) => {
+            return Err(format!(
+                "Unable to parse the input for day {day}, {}",
+                describe_parse_error(&input, error)
+            ));
+        }
+        (OutputFormat::Text, DayOutcome::Completed { result, .. }) => {
+            let allocations = |phase: usize| {
+                result
+                    .allocations
+                    .map_or(String::new(), |a| format!(" ({})", a[phase]))
+            };
+            println!("Parse time: {:?}{}", result.parse_time, allocations(0));
+            if parts.includes(1) {
+                println!("Part one time: {:?}{}", result.p1_time, allocations(1));
+            }
+            if parts.includes(2) {
+                println!("Part two time: {:?}{}", result.p2_time, allocations(2));
+            }
+            println!("Total Time: {:?}{}", result.total_time(), allocations(3));
+            let answers = [("P1", &result.p1_answer), ("P2", &result.p2_answer)];
+            for ((label, answer), expected) in answers.into_iter().zip(&expected) {
+                let Some(answer) = answer else { continue };
+                match (example_check(Some(answer), expected.as_deref()), expected) {
+                    (Some(true), _) => println!("{label}: {answer} (matches the example)"),
+                    (Some(false), Some(expected)) => {
+                        println!("{label}: {answer} (the example's answer is {expected})")
+                    }
+                    _ => println!("{label}: {answer}"),
+                }
+            }
+        }
+        _ => print_outcomes(&[(day, outcome)], format),
+    }
+
+    if let Some(n) = wrong_example {
+        return Err(format!("Day {day} doesn't give the answers of example {n}"));
+    }
+    Ok(())
+}
+
+pub fn execute_days(
+    year: u16,
+    days: RangeInclusive<u8>,
+    source: &InputSource,
+    parts: Parts,
+    format: OutputFormat,
+) {
+    let outcomes = run_days(year, days, source, parts);
+    print_outcomes(&outcomes, format);
+}
+
+pub fn run_days(
+    year: u16,
+    days: RangeInclusive<u8>,
+    source: &InputSource,
+    parts: Parts,
+) -> Vec<(u8, DayOutcome)> {
+    let mut outcomes = vec![];
+    for day in days {
+        let Some(implemented) = executors::get_executor(year, day).map(|e| e.is_implemented())
+        else {
+            outcomes.push((day, DayOutcome::Skipped("not registered")));
+            continue;
+        };
+        let outcome = if !implemented {
+            DayOutcome::Skipped("not implemented")
+        } else {
+            match (load_input(year, day, source), source) {
+                (Ok((input, kind)), _) => {
+                    let mut executor = executors::get_executor(year, day).expect("registered");
+                    executor.set_input_kind(kind);
+                    complete_day(executor, &input, parts)
+                }
+                (Err(_), InputSource::Example(_)) => DayOutcome::Skipped("no such example"),
+                (Err(_), _) => DayOutcome::Skipped("no input"),
+            }
+        };
+        outcomes.push((day, outcome));
+    }
+    outcomes
+}
+
+fn print_outcomes(outcomes: &[(u8, DayOutcome)], format: OutputFormat) {
+    match format {
+        OutputFormat::Text => print_summary(outcomes),
+        OutputFormat::Json => report::print_json(outcomes),
+        OutputFormat::Csv => report::print_csv(outcomes),
+    }
+}
+
+fn print_summary(outcomes: &[(u8, DayOutcome)]) {
+    println!(
+        "{:>3} | {:>12} | {:>12} | {:>12} | {:>12} | {:<20} | {:<20}",
+        "Day", "Parse", "Part one", "Part two", "Total", "Part one answer", "Part two answer"
+    );
+    println!("{}", "-".repeat(127));
+
+    let mut totals = [Duration::ZERO; 4];
+    for (day, outcome) in outcomes {
+        match outcome {
+            DayOutcome::Completed { result, .. } => {
+                let times = [
+                    result.parse_time,
+                    result.p1_time,
+                    result.p2_time,
+                    result.total_time(),
+                ];
+                for (total, time) in totals.iter_mut().zip(times) {
+                    *total += time;
+                }
+                println!(
+                    "{:>3} | {:>12} | {:>12} | {:>12} | {:>12} | {:<20} | {:<20}",
+                    day,
+                    format!("{:?}", times[0]),
+                    format!("{:?}", times[1]),
+                    format!("{:?}", times[2]),
+                    format!("{:?}", times[3]),
+                    display_answer(&result.p1_answer),
+                    display_answer(&result.p2_answer),
+                );
+                if let Some(allocations) = result.allocations {
+                    print_allocation_rows(allocations);
+                }
+            }
+            DayOutcome::ParseFailed(error) => {
+                println!("{day:>3} | failed to parse ({error})");
+            }
+            DayOutcome::Skipped(reason) => {
+                println!("{day:>3} | skipped ({reason})");
+            }
+        }
+    }
+
+    println!("{}", "-".repeat(127));
+    println!(
+        "{:>3} | {:>12} | {:>12} | {:>12} | {:>12} |",
+        "All",
+        format!("{:?}", totals[0]),
+        format!("{:?}", totals[1]),
+        format!("{:?}", totals[2]),
+        format!("{:?}", totals[3]),
+    );
+}
+
+/// Rows under a day in the summary with the allocations of each phase and
+/// of the whole run
+fn print_allocation_rows(phases: [AllocStats; 4]) {
+    let rows = [
+        ("allocations", phases.map(|a| a.allocations.to_string())),
+        (
+            "allocated",
+            phases.map(|a| alloc_metrics::format_bytes(a.bytes)),
+        ),
+        (
+            "peak live",
+            phases.map(|a| alloc_metrics::format_bytes(a.peak_bytes as u64)),
+        ),
+    ];
+    for (label, cells) in rows {
+        println!(
+            "{:>3} | {:>12} | {:>12} | {:>12} | {:>12} | {}",
+            "", cells[0], cells[1], cells[2], cells[3], label,
+        );
+    }
+}
+
+fn display_answer(answer: &Option<Answer>) -> String {
+    answer.as_ref().map_or(String::new(), Answer::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_answers_that_can_be_checked_are() {
+        let answer = Answer::from(142u32);
+        assert_eq!(example_check(Some(&answer), Some("142")), Some(true));
+        assert_eq!(example_check(Some(&answer), Some("143")), Some(false));
+        assert_eq!(example_check(Some(&answer), None), None);
+        assert_eq!(
+            example_check(Some(&Answer::NotImplemented), Some("142")),
+            None
+        );
+        assert_eq!(example_check(None, Some("142")), None);
+    }
 
-    println!("Parse time: {:?}", parse_time);
-    println!("Part one time: {:?}", p1_time - parse_time);
-    println!("Part two time: {:?}", p2_time - p1_time);
-    println!("Total Time: {:?}", p2_time);
-    println!("{}", p1_buffer);
-    println!("{}", p2_buffer);
+    #[test]
+    fn normalized_inputs_differ_only_in_content() {
+        let saved_on_windows =
+            "\u{feff}seeds: 79 14\r\n\r\nseed-to-soil map:  \r\n50 98 2\r\n\r\n\r\n";
+        assert_eq!(
+            normalize_input(saved_on_windows),
+            "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n"
+        );
+        assert_eq!(
+            normalize_input("RL\n\nAAA = (BBB, CCC)"),
+            "RL\n\nAAA = (BBB, CCC)\n"
+        );
+        assert_eq!(normalize_input("\n\n"), "");
+    }
 }
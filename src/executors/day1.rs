@@ -1,22 +1,57 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::lines;
 
 const DIGITS: [&str; 9] = [
     "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
 
 #[derive(Default)]
-pub struct Day1 {
-    input: String,
+pub struct Day1<'a> {
+    input: &'a str,
 }
-impl Executor for Day1 {
-    fn parse(&mut self, input: String) {
-        self.input = input
+
+const EXAMPLES: [Example; 2] = [
+    Example {
+        input: "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+",
+        part_one: Some("142"),
+        part_two: None,
+    },
+    Example {
+        input: "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+",
+        part_one: None,
+        part_two: Some("281"),
+    },
+];
+
+impl<'a> Executor<'a> for Day1<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        // Lines with only spelled out digits count for nothing in part one,
+        // but a line without any digit can't be calibrated at all
+        for (i, line) in lines(input) {
+            if !line.char_indices().any(|(j, c)| get_digit(c, &line[j..]).is_some()) {
+                return Err(ParseError::in_line(i, 0, "line has no digit"));
+            }
+        }
+        self.input = input;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let mut total = 0;
-        for line in self.input.lines() {
+        for (_, line) in lines(self.input) {
             let mut last_digit = None;
             for c in line.chars() {
                 if let Some(d) = c.to_digit(10) {
@@ -26,17 +61,17 @@ impl Executor for Day1 {
                     last_digit = Some(d);
                 }
             }
-            total += last_digit.unwrap()
+            total += last_digit.unwrap_or_default()
         }
 
-        _ = write!(output_buffer, "P1: {total}");
+        total.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let mut total = 0;
-        for line in self.input.lines() {
+        for (_, line) in lines(self.input) {
             let mut last_digit = None;
-            for (i, c) in line.chars().enumerate() {
+            for (i, c) in line.char_indices() {
                 if let Some(d) = get_digit(c, &line[i..]) {
                     if last_digit.is_none() {
                         total += d * 10;
@@ -44,9 +79,13 @@ impl Executor for Day1 {
                     last_digit = Some(d);
                 }
             }
-            total += last_digit.unwrap()
+            total += last_digit.unwrap_or_default()
         }
-        _ = write!(output_buffer, "P2: {total}");
+        total.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
 
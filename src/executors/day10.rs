@@ -1,7 +1,7 @@
-use super::Executor;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid;
 
 use std::collections::VecDeque;
-use std::fmt::Write;
 
 use crate::utils::direction::Direction;
 
@@ -41,17 +41,17 @@ impl TileType {
 }
 
 impl TileType {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '|' => TileType::Vertical,
-            '-' => TileType::Horizontal,
-            'L' => TileType::NorthEastBend,
-            'J' => TileType::NorthWestBend,
-            '7' => TileType::SouthWestBend,
-            'F' => TileType::SouthEastBend,
-            '.' => TileType::Ground,
-            'S' => TileType::Start,
-            _ => unsafe { std::hint::unreachable_unchecked() },
+            '|' => Some(TileType::Vertical),
+            '-' => Some(TileType::Horizontal),
+            'L' => Some(TileType::NorthEastBend),
+            'J' => Some(TileType::NorthWestBend),
+            '7' => Some(TileType::SouthWestBend),
+            'F' => Some(TileType::SouthEastBend),
+            '.' => Some(TileType::Ground),
+            'S' => Some(TileType::Start),
+            _ => None,
         }
     }
 }
@@ -63,9 +63,35 @@ struct Tile(TileType, bool);
 pub struct Day10 {
     tiles: Vec<Vec<Tile>>,
     start: (usize, usize),
+    farthest_distance: usize,
 }
 
 impl Day10 {
+    /// Walks the loop out from the start, marking every tile on it and
+    /// recording how far the farthest tile is
+    fn trace_loop(&mut self) {
+        let mut max_depth = 0;
+        let mut to_visit = VecDeque::new();
+        let (i, j) = self.start;
+        to_visit.push_back((i, j, 0));
+
+        while let Some((i, j, depth)) = to_visit.pop_front() {
+            max_depth = std::cmp::max(depth, max_depth);
+            for direction in DIRECTIONS {
+                if self.tiles[i][j].0.connects(direction) {
+                    let (i_offset, j_offset) = direction.get_offset();
+                    let next_i = (i as isize + i_offset as isize) as usize;
+                    let next_j = (j as isize + j_offset as isize) as usize;
+                    if !self.tiles[next_i][next_j].1 {
+                        self.tiles[next_i][next_j].1 = true;
+                        to_visit.push_back((next_i, next_j, depth + 1));
+                    }
+                }
+            }
+        }
+        self.farthest_distance = max_depth;
+    }
+
     fn resolve_start_tile(&mut self) {
         let Self { tiles, start, .. } = self;
         let &mut (i, j) = start;
@@ -123,46 +149,90 @@ impl Day10 {
     }
 }
 
-impl Executor for Day10 {
-    fn parse(&mut self, input: String) {
-        for (i, line) in input.lines().enumerate() {
+const EXAMPLES: [Example; 4] = [
+    Example {
+        input: "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....
+",
+        part_one: Some("4"),
+        part_two: None,
+    },
+    Example {
+        input: "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+",
+        part_one: Some("8"),
+        part_two: None,
+    },
+    Example {
+        input: "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+",
+        part_one: None,
+        part_two: Some("4"),
+    },
+    Example {
+        input: "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+",
+        part_one: None,
+        part_two: Some("4"),
+    },
+];
+
+impl Executor<'_> for Day10 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let mut start = None;
+        for (i, line) in grid(input)?.into_iter().enumerate() {
             let mut row = vec![];
             for (j, c) in line.chars().enumerate() {
-                let tile_type = TileType::from_char(c);
+                let tile_type =
+                    TileType::from_char(c).ok_or_else(|| ParseError::unexpected_char(i, j, c))?;
                 if tile_type == TileType::Start {
-                    self.start = (i, j);
+                    start = Some((i, j));
                 }
                 row.push(Tile(tile_type, false));
             }
             self.tiles.push(row);
         }
+        let Some(start) = start else {
+            return Err(ParseError::at(input, &input[input.len()..], "no start tile"));
+        };
+        self.start = start;
         self.resolve_start_tile();
+        self.trace_loop();
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        let mut max_depth = 0;
-        let mut to_visit = VecDeque::new();
-        let (i, j) = self.start;
-        to_visit.push_back((i, j, 0));
-
-        while let Some((i, j, depth)) = to_visit.pop_front() {
-            max_depth = std::cmp::max(depth, max_depth);
-            for direction in DIRECTIONS {
-                if self.tiles[i][j].0.connects(direction) {
-                    let (i_offset, j_offset) = direction.get_offset();
-                    let next_i = (i as isize + i_offset as isize) as usize;
-                    let next_j = (j as isize + j_offset as isize) as usize;
-                    if !self.tiles[next_i][next_j].1 {
-                        self.tiles[next_i][next_j].1 = true;
-                        to_visit.push_back((next_i, next_j, depth + 1));
-                    }
-                }
-            }
-        }
-        _ = write!(output_buffer, "P1: {max_depth}");
+    fn part_one(&self) -> Answer {
+        self.farthest_distance.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let mut num_enclosed = 0;
         for row in self.tiles.iter() {
             let mut inside_loop = false;
@@ -171,21 +241,23 @@ impl Executor for Day10 {
                     Tile(t, true) if t.connects(Direction::North) => {
                         inside_loop = !inside_loop;
                     }
-                    Tile(_, false) => {
-                        if inside_loop {
-                            num_enclosed += 1;
-                        }
+                    Tile(_, false) if inside_loop => {
+                        num_enclosed += 1;
                     }
                     _ => {}
                 }
             }
         }
-        _ = write!(output_buffer, "P2 {num_enclosed}");
+        num_enclosed.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
 
 fn out_of_bounds<T>(
-    grid: &Vec<Vec<T>>,
+    grid: &[Vec<T>],
     (i, j): (usize, usize),
     (i_offset, j_offset): (i32, i32),
 ) -> bool {
@@ -1,7 +1,7 @@
 use rayon::prelude::*;
 
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid;
 
 const P1_EXPANSION_FACTOR: usize = 2;
 const P2_EXPANSION_FACTOR: usize = 1_000_000;
@@ -11,7 +11,6 @@ pub struct Day11 {
     populated_column_counts: Vec<u8>,
     populated_row_counts: Vec<u8>,
     stars: Vec<(u8, u8)>,
-    p2_result: usize,
 }
 
 impl Day11 {
@@ -27,38 +26,72 @@ impl Day11 {
                 - self.populated_column_counts[smaller_col as usize])
     }
 
-    fn get_distance(&self, (r1, c1): (u8, u8), (r2, c2): (u8, u8)) -> (usize, usize) {
+    fn get_distance(
+        &self,
+        (r1, c1): (u8, u8),
+        (r2, c2): (u8, u8),
+        expansion_factor: usize,
+    ) -> usize {
         let base_distance = (r2 - r1) as usize + (c1 as isize - c2 as isize).unsigned_abs();
         let (min_c, max_c) = (std::cmp::min(c1, c2), std::cmp::max(c1, c2));
         let num_empty_rows = self.get_empty_rows_between(r1, r2) as usize;
         let num_empty_cols = self.get_empty_cols_between(min_c, max_c) as usize;
 
-        let mut p1_expansion_distance = (num_empty_rows * P1_EXPANSION_FACTOR) - num_empty_rows;
-        p1_expansion_distance += (num_empty_cols * P1_EXPANSION_FACTOR) - num_empty_cols;
+        let mut expansion_distance = (num_empty_rows * expansion_factor) - num_empty_rows;
+        expansion_distance += (num_empty_cols * expansion_factor) - num_empty_cols;
 
-        let mut p2_expansion_distance = (num_empty_rows * P2_EXPANSION_FACTOR) - num_empty_rows;
-        p2_expansion_distance += (num_empty_cols * P2_EXPANSION_FACTOR) - num_empty_cols;
+        base_distance + expansion_distance
+    }
 
-        (
-            base_distance + p1_expansion_distance,
-            base_distance + p2_expansion_distance,
-        )
+    fn sum_distances(&self, expansion_factor: usize) -> usize {
+        self.stars
+            .par_iter()
+            .enumerate()
+            .map(|(i, s1)| {
+                self.stars[i + 1..]
+                    .par_iter()
+                    .map(|s2| self.get_distance(*s1, *s2, expansion_factor))
+                    .sum::<usize>()
+            })
+            .sum()
     }
 }
 
-impl Executor for Day11 {
-    fn parse(&mut self, input: String) {
-        for _ in input.lines().next().unwrap().as_bytes() {
-            self.populated_column_counts.push(0);
-        }
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+",
+        part_one: Some("374"),
+        part_two: Some("82000210"),
+    },
+];
+
+impl Executor<'_> for Day11 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let rows = grid(input)?;
+        self.populated_column_counts = vec![0; rows[0].len()];
 
-        for (i, line) in input.lines().enumerate() {
+        for (i, line) in rows.into_iter().enumerate() {
             let mut row_populated = 0;
             for (j, c) in line.chars().enumerate() {
-                if c == '#' {
-                    row_populated = 1;
-                    self.stars.push((i as u8, j as u8));
-                    self.populated_column_counts[j] = 1;
+                match c {
+                    '#' => {
+                        row_populated = 1;
+                        self.stars.push((i as u8, j as u8));
+                        self.populated_column_counts[j] = 1;
+                    }
+                    '.' => {}
+                    _ => return Err(ParseError::unexpected_char(i, j, c)),
                 }
             }
             self.populated_row_counts.push(row_populated);
@@ -73,31 +106,18 @@ impl Executor for Day11 {
             *col_count += populated_so_far;
             populated_so_far = *col_count
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        let (p1_total, p2_total) = self
-            .stars
-            .par_iter()
-            .enumerate()
-            .map(|(i, s1)| {
-                self.stars[i + 1..]
-                    .par_iter()
-                    .map(|s2| self.get_distance(*s1, *s2))
-                    .reduce(
-                        || (0, 0),
-                        |(p1_acc, p2_acc), (p1, p2)| (p1 + p1_acc, p2 + p2_acc),
-                    )
-            })
-            .reduce(
-                || (0, 0),
-                |(p1_acc, p2_acc), (p1, p2)| (p1 + p1_acc, p2 + p2_acc),
-            );
-        self.p2_result = p2_total;
-        _ = write!(output_buffer, "P1: {p1_total}");
+    fn part_one(&self) -> Answer {
+        self.sum_distances(P1_EXPANSION_FACTOR).into()
+    }
+
+    fn part_two(&self) -> Answer {
+        self.sum_distances(P2_EXPANSION_FACTOR).into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        _ = write!(output_buffer, "P2: {}", self.p2_result);
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,8 +1,8 @@
 use rayon::prelude::*;
 use tinyvec::ArrayVec;
 
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::lines;
 
 type SpringBacking = [HotSpringCondition; 128];
 type GroupBacking = [u8; 32];
@@ -15,13 +15,15 @@ enum HotSpringCondition {
     Unknown,
 }
 
-impl std::convert::From<char> for HotSpringCondition {
-    fn from(c: char) -> Self {
+impl std::convert::TryFrom<char> for HotSpringCondition {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, char> {
         match c {
-            '#' => HotSpringCondition::Damaged,
-            '.' => HotSpringCondition::Operational,
-            '?' => HotSpringCondition::Unknown,
-            _ => unsafe { std::hint::unreachable_unchecked() },
+            '#' => Ok(HotSpringCondition::Damaged),
+            '.' => Ok(HotSpringCondition::Operational),
+            '?' => Ok(HotSpringCondition::Unknown),
+            _ => Err(c),
         }
     }
 }
@@ -29,21 +31,45 @@ impl std::convert::From<char> for HotSpringCondition {
 #[derive(Debug)]
 struct SpringData(ArrayVec<SpringBacking>, ArrayVec<GroupBacking>);
 impl SpringData {
-    fn parse(input: &str) -> Self {
+    /// Errors are reported as if `line` were the first line of the input
+    fn parse(line: &str) -> Result<Self, ParseError> {
         let mut spring_vec = ArrayVec::<_>::default();
         let mut group_vec = ArrayVec::<_>::default();
 
-        let mut iter = input.split_whitespace();
-        let springs = iter.next().unwrap();
-        for c in springs.chars() {
-            spring_vec.push(c.into())
+        let Some((springs, groups)) = line.split_once(' ') else {
+            let end = &line[line.len()..];
+            return Err(ParseError::at(line, end, "expected a space before the groups"));
+        };
+        for (k, c) in springs.char_indices() {
+            let spring = HotSpringCondition::try_from(c).map_err(|c| {
+                ParseError::at(line, &springs[k..], format!("unexpected character {c:?}"))
+            })?;
+            if spring_vec.try_push(spring).is_some() {
+                return Err(ParseError::at(line, &springs[k..], "too many springs"));
+            }
         }
-        let groups = iter.next().unwrap();
         for g in groups.split(',') {
-            group_vec.push(g.parse().unwrap());
+            let group = g
+                .parse()
+                .map_err(|_| ParseError::at(line, g, format!("invalid group size {g:?}")))?;
+            if group_vec.try_push(group).is_some() {
+                return Err(ParseError::at(line, g, "too many groups"));
+            }
         }
 
-        SpringData(spring_vec, group_vec)
+        Ok(SpringData(spring_vec, group_vec))
+    }
+
+    /// Five copies of the record, with the springs joined by unknowns
+    fn unfolded(&self) -> Self {
+        let &SpringData(original_springs, original_groups) = self;
+        let (mut springs, mut groups) = (original_springs, original_groups);
+        for _ in 0..4 {
+            springs.push(HotSpringCondition::Unknown);
+            springs.extend_from_slice(&original_springs);
+            groups.extend_from_slice(&original_groups);
+        }
+        SpringData(springs, groups)
     }
 }
 
@@ -134,44 +160,51 @@ pub struct Day12 {
     spring_data: Vec<SpringData>,
 }
 
-impl Executor for Day12 {
-    fn parse(&mut self, input: String) {
-        for line in input.lines() {
-            self.spring_data.push(SpringData::parse(line));
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+",
+        part_one: Some("21"),
+        part_two: Some("525152"),
+    },
+];
+
+impl Executor<'_> for Day12 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        for (i, line) in lines(input) {
+            let spring_data =
+                SpringData::parse(line).map_err(|e| ParseError { line: i + 1, ..e })?;
+            self.spring_data.push(spring_data);
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let out = self
             .spring_data
             .par_iter()
             .map(SpringData::count_possible_arrangements)
             .sum::<u64>();
-        _ = write!(output_buffer, "P1: {out}");
-    }
-
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        self.spring_data
-            .par_iter_mut()
-            .for_each(|SpringData(springs, groups)| {
-                let original_springs_size = springs.len();
-                let original_groups_size = groups.len();
-                for _ in 0..4 {
-                    springs.push(HotSpringCondition::Unknown);
-                    for s in 0..original_springs_size {
-                        springs.push(springs[s]);
-                    }
-                    for g in 0..original_groups_size {
-                        groups.push(groups[g]);
-                    }
-                }
-            });
+        out.into()
+    }
+
+    fn part_two(&self) -> Answer {
         let out = self
             .spring_data
             .par_iter()
-            .map(SpringData::count_possible_arrangements)
+            .map(|spring_data| spring_data.unfolded().count_possible_arrangements())
             .sum::<u64>();
-        _ = write!(output_buffer, "P2: {out}");
+        out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
 
@@ -187,63 +220,63 @@ mod tests {
 
     #[test]
     fn cardinality_no_unknowns() {
-        let spring_data = SpringData::parse("# 1");
+        let spring_data = SpringData::parse("# 1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
     }
     #[test]
     fn cardinality_one_unknown() {
-        let spring_data = SpringData::parse("?# 2");
+        let spring_data = SpringData::parse("?# 2").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
     }
 
     #[test]
     fn cardinality_multiple_unknowns_simple() {
-        let spring_data = SpringData::parse("??.?.? 1,1,1");
+        let spring_data = SpringData::parse("??.?.? 1,1,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 2);
     }
 
     #[test]
     fn cardinality_unknowns_preceding_damaged() {
-        let spring_data = SpringData::parse("?.# 1,1");
+        let spring_data = SpringData::parse("?.# 1,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
     }
 
     #[test]
     fn cardinality_unknowns_following_damaged() {
-        let spring_data = SpringData::parse("#?? 1,1");
+        let spring_data = SpringData::parse("#?? 1,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
     }
 
     #[test]
     fn cardinality_corner_case_1() {
-        let spring_data = SpringData::parse("???? 1,1");
+        let spring_data = SpringData::parse("???? 1,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 3);
     }
 
     #[test]
     fn cardinality_corner_case_2() {
-        let spring_data = SpringData::parse("????.?? 2,2");
+        let spring_data = SpringData::parse("????.?? 2,2").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 3);
     }
 
     #[test]
     fn p1_sample_input() {
-        let spring_data = SpringData::parse("???.### 1,1,3");
+        let spring_data = SpringData::parse("???.### 1,1,3").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
 
-        let spring_data = SpringData::parse(".??..??...?##. 1,1,3");
+        let spring_data = SpringData::parse(".??..??...?##. 1,1,3").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 4);
 
-        let spring_data = SpringData::parse("?#?#?#?#?#?#?#? 1,3,1,6");
+        let spring_data = SpringData::parse("?#?#?#?#?#?#?#? 1,3,1,6").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
 
-        let spring_data = SpringData::parse("????.#...#... 4,1,1");
+        let spring_data = SpringData::parse("????.#...#... 4,1,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 1);
 
-        let spring_data = SpringData::parse("????.######..#####. 1,6,5");
+        let spring_data = SpringData::parse("????.######..#####. 1,6,5").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 4);
 
-        let spring_data = SpringData::parse("?###???????? 3,2,1");
+        let spring_data = SpringData::parse("?###???????? 3,2,1").unwrap();
         assert_eq!(spring_data.count_possible_arrangements(), 10);
     }
 }
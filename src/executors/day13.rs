@@ -1,12 +1,12 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::{grids, lines};
 
 #[derive(Default)]
-pub struct Day13 {
-    input: Vec<Vec<Vec<u8>>>,
+pub struct Day13<'a> {
+    input: Vec<Vec<&'a [u8]>>,
 }
 
-fn get_horizontal_line_of_reflection(input: &Vec<Vec<u8>>, num_smudges: i32) -> Option<usize> {
+fn get_horizontal_line_of_reflection(input: &[&[u8]], num_smudges: i32) -> Option<usize> {
     for divider in 1..input[0].len() {
         let mut smudges = 0;
         for line in input {
@@ -28,7 +28,7 @@ fn get_horizontal_line_of_reflection(input: &Vec<Vec<u8>>, num_smudges: i32) ->
     None
 }
 
-fn get_vertical_line_of_reflection(input: &Vec<Vec<u8>>, num_smudges: i32) -> Option<usize> {
+fn get_vertical_line_of_reflection(input: &[&[u8]], num_smudges: i32) -> Option<usize> {
     for divider in 1..input.len() {
         let mut smudges = 0;
         for i in 0..input[0].len() {
@@ -49,19 +49,45 @@ fn get_vertical_line_of_reflection(input: &Vec<Vec<u8>>, num_smudges: i32) -> Op
     None
 }
 
-impl Executor for Day13 {
-    fn parse(&mut self, input: String) {
-        self.input = input
-            .split("\n\n")
-            .map(|s| {
-                s.lines()
-                    .map(|l| l.as_bytes().to_vec())
-                    .collect()
-            })
-            .collect()
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.##..##.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+",
+        part_one: Some("405"),
+        part_two: Some("400"),
+    },
+];
+
+impl<'a> Executor<'a> for Day13<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        for (i, line) in lines(input) {
+            if let Some((j, c)) = line.chars().enumerate().find(|&(_, c)| c != '#' && c != '.') {
+                return Err(ParseError::unexpected_char(i, j, c));
+            }
+        }
+        self.input = grids(input)?
+            .into_iter()
+            .map(|rows| rows.into_iter().map(str::as_bytes).collect())
+            .collect();
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let out = self
             .input
             .iter()
@@ -70,10 +96,10 @@ impl Executor for Day13 {
                     + get_horizontal_line_of_reflection(i, 0).unwrap_or_default()
             })
             .sum::<usize>();
-        _ = write!(output_buffer, "P1: {out}");
+        out.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let out = self
             .input
             .iter()
@@ -82,6 +108,10 @@ impl Executor for Day13 {
                     + get_horizontal_line_of_reflection(i, 1).unwrap_or_default()
             })
             .sum::<usize>();
-        _ = write!(output_buffer, "P2: {out}");
+        out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,7 +1,7 @@
 use rustc_hash::FxHashSet;
 
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid_within;
 
 use crate::utils::direction::Direction;
 use crate::utils::point::*;
@@ -29,21 +29,26 @@ impl std::fmt::Debug for Tile {
     }
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Tile {
+impl TryFrom<char> for Tile {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Tile, char> {
         match c {
-            '.' => Tile::Empty,
-            'O' => Tile::Rock,
-            '#' => Tile::Cube,
-            _ => unreachable!(),
+            '.' => Ok(Tile::Empty),
+            'O' => Ok(Tile::Rock),
+            '#' => Ok(Tile::Cube),
+            _ => Err(c),
         }
     }
 }
 
+/// Each row's rocks are digested into the bits of a `u128`
+const MAX_SIZE: usize = 128;
+
 #[derive(Default, Debug, Clone, Hash, PartialEq)]
 pub struct Platform {
     rocks: Vec<Vec<Tile>>,
-    digest: Option<[u128; 128]>,
+    digest: Option<[u128; MAX_SIZE]>,
 }
 
 impl Platform {
@@ -116,7 +121,7 @@ impl Platform {
     }
 
     fn compute_digest(&mut self) {
-        let mut out = [0u128; 128];
+        let mut out = [0u128; MAX_SIZE];
         for (i, row) in self.rocks.iter().enumerate() {
             for j in 0..row.len() {
                 if self.rocks[i][j] == Tile::Rock {
@@ -127,7 +132,7 @@ impl Platform {
         self.digest = Some(out);
     }
 
-    fn get_rock_digest(&self) -> [u128; 128] {
+    fn get_rock_digest(&self) -> [u128; MAX_SIZE] {
         unsafe { self.digest.unwrap_unchecked() }
     }
 }
@@ -137,11 +142,35 @@ pub struct Day14 {
     platform: Platform,
 }
 
-impl Executor for Day14 {
-    fn parse(&mut self, input: String) {
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+",
+        part_one: Some("136"),
+        part_two: Some("64"),
+    },
+];
+
+impl Executor<'_> for Day14 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
         let mut rocks = vec![];
-        for line in input.lines() {
-            rocks.push(line.chars().map(Tile::from).collect())
+        for (i, line) in grid_within(input, MAX_SIZE, MAX_SIZE)?.into_iter().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(j, c)| Tile::try_from(c).map_err(|c| ParseError::unexpected_char(i, j, c)))
+                .collect::<Result<_, _>>()?;
+            rocks.push(row)
         }
         let mut platform = Platform {
             rocks,
@@ -149,14 +178,16 @@ impl Executor for Day14 {
         };
         platform.compute_digest();
         self.platform = platform;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        self.platform.tilt(Direction::North);
-        _ = write!(output_buffer, "P1: {}", self.platform.calculate_load());
+    fn part_one(&self) -> Answer {
+        let mut platform = self.platform.clone();
+        platform.tilt(Direction::North);
+        platform.calculate_load().into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let tilts = [
             Direction::North,
             Direction::West,
@@ -164,17 +195,16 @@ impl Executor for Day14 {
             Direction::East,
         ];
 
+        let mut platform = self.platform.clone();
         let mut previous_tile_states = FxHashSet::default();
         let mut cycles = 0;
         let mut start_cycle = 0;
         let mut encountered = false;
-        // Skip 1 because we already tilted everything North in part one and
-        // we don't want to clone everything unnecessarily
-        for &t in tilts.iter().cycle().skip(1) {
-            self.platform.tilt(t);
+        for &t in tilts.iter().cycle() {
+            platform.tilt(t);
             if t == Direction::East {
                 cycles += 1;
-                let digest = &self.platform.get_rock_digest();
+                let digest = &platform.get_rock_digest();
                 if previous_tile_states.contains(digest) {
                     if encountered {
                         break;
@@ -191,9 +221,13 @@ impl Executor for Day14 {
         let remaining_cycles = (P2_CYCLE_NUM - cycles) % cycle_length;
         for _ in 0..remaining_cycles {
             for t in tilts {
-                self.platform.tilt(t);
+                platform.tilt(t);
             }
         }
-        _ = write!(output_buffer, "P2: {}", self.platform.calculate_load());
+        platform.calculate_load().into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
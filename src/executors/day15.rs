@@ -1,10 +1,44 @@
-use super::Executor;
-
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
 
 #[derive(Default)]
-pub struct Day15 {
-    input: String,
+pub struct Day15<'a> {
+    steps: Vec<Step<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Remove,
+    Insert(u32),
+}
+
+/// One comma separated step of the initialization sequence, as written and
+/// broken into its parts
+struct Step<'a> {
+    text: &'a str,
+    label: &'a str,
+    operation: Operation,
+}
+
+/// Parses a step, a label followed by `-` or by `=` and a focal length digit.
+/// `text` must be a slice of `input`.
+fn parse_step<'a>(input: &str, text: &'a str) -> Result<Step<'a>, ParseError> {
+    let label_end = text.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(text.len());
+    if label_end == 0 {
+        return Err(ParseError::at(input, text, "expected a label"));
+    }
+    let (label, rest) = text.split_at(label_end);
+    let (operation, length) = match rest.as_bytes() {
+        [b'-', ..] => (Operation::Remove, 1),
+        [b'=', digit @ b'1'..=b'9', ..] => (Operation::Insert((digit - b'0') as u32), 2),
+        [b'=', ..] => {
+            return Err(ParseError::at(input, &rest[1..], "expected a focal length from 1 to 9"))
+        }
+        _ => return Err(ParseError::at(input, rest, "expected '-' or '=' after the label")),
+    };
+    if rest.len() > length {
+        return Err(ParseError::at(input, &rest[length..], "expected ',' after the step"));
+    }
+    Ok(Step { text, label, operation })
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -23,38 +57,46 @@ fn run_hash_algorithm(s: &str) -> usize {
     current_value
 }
 
-impl Executor for Day15 {
-    fn parse(&mut self, input: String) {
-        self.input = input;
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+",
+        part_one: Some("1320"),
+        part_two: Some("145"),
+    },
+];
+
+impl<'a> Executor<'a> for Day15<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        self.steps = input
+            .trim_end_matches('\n')
+            .split(',')
+            .map(|text| parse_step(input, text))
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        let out: usize = self.input.trim().split(',').map(run_hash_algorithm).sum();
-        _ = write!(output_buffer, "P1: {}", out);
+    fn part_one(&self) -> Answer {
+        let out: usize = self.steps.iter().map(|step| run_hash_algorithm(step.text)).sum();
+        out.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let mut boxes: [Vec<Lens>; 256] = std::array::from_fn(|_| vec![]);
-        for instruction in self.input.trim().split(',') {
-            let op_idx = instruction.find(|c| c == '-' || c == '=').unwrap();
-            let label = &instruction[..op_idx];
-            let raw_focal_power = &instruction[op_idx + 1..];
+        for &Step { label, operation, .. } in &self.steps {
             let lens_box = run_hash_algorithm(label);
             let lens_position = boxes[lens_box].iter().position(|&l| l.label == label);
-            match &instruction[op_idx..=op_idx] {
-                "-" => {
+            match operation {
+                Operation::Remove => {
                     if let Some(index) = lens_position {
                         boxes[lens_box].remove(index);
                     }
                 }
-                "=" => {
-                    let focal_power = raw_focal_power.parse().unwrap();
-                    match lens_position {
-                        Some(index) => boxes[lens_box][index].focal_power = focal_power,
-                        None => boxes[lens_box].push(Lens { label, focal_power }),
-                    }
-                }
-                _ => unreachable!(),
+                Operation::Insert(focal_power) => match lens_position {
+                    Some(index) => boxes[lens_box][index].focal_power = focal_power,
+                    None => boxes[lens_box].push(Lens { label, focal_power }),
+                },
             }
         }
 
@@ -71,6 +113,10 @@ impl Executor for Day15 {
                     .sum::<usize>()
             })
             .sum();
-        _ = write!(output_buffer, "P2: {out}");
+        out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,9 +1,13 @@
-use super::Executor;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid_within;
 
-use std::{collections::VecDeque, fmt::Write};
+use std::collections::VecDeque;
 
 use rayon::prelude::*;
 
+/// The widest and tallest grid the visited tiles have room for
+const MAX_SIZE: usize = 128;
+
 #[derive(Debug, Clone, Copy)]
 enum Tile {
     Empty,
@@ -13,19 +17,27 @@ enum Tile {
     EWSplitter,
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Tile {
-        match c {
-            '.' => Tile::Empty,
-            '/' => Tile::NEMirror,
-            '\\' => Tile::SEMirror,
-            '-' => Tile::EWSplitter,
-            '|' => Tile::NSSplitter,
-            _ => unreachable!(),
+impl Tile {
+    fn from_byte(b: u8) -> Option<Tile> {
+        match b {
+            b'.' => Some(Tile::Empty),
+            b'/' => Some(Tile::NEMirror),
+            b'\\' => Some(Tile::SEMirror),
+            b'-' => Some(Tile::EWSplitter),
+            b'|' => Some(Tile::NSSplitter),
+            _ => None,
         }
     }
 }
 
+impl TryFrom<char> for Tile {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Tile, char> {
+        u8::try_from(c).ok().and_then(Tile::from_byte).ok_or(c)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 enum Direction {
     North,
@@ -85,11 +97,13 @@ impl Direction {
 }
 
 #[derive(Default)]
-pub struct Day16 {
-    grid: Vec<Vec<Tile>>,
+pub struct Day16<'a> {
+    /// Rows borrowed straight from the input, which parsing checked are all
+    /// tiles
+    grid: Vec<&'a [u8]>,
 }
 
-impl Day16 {
+impl Day16<'_> {
     fn in_bounds(&self, Point(i, j): Point) -> bool {
         i >= 0 && j >= 0 && (i as usize) < self.grid.len() && (j as usize) < self.grid[0].len()
     }
@@ -102,7 +116,7 @@ impl Day16 {
     ) -> u32 {
         to_visit.clear();
 
-        let mut visited = [[0u8; 128]; 128];
+        let mut visited = [[0u8; MAX_SIZE]; MAX_SIZE];
         visited[starting_point.0 as usize][starting_point.1 as usize] |=
             starting_dir.get_visited_bitmask();
         to_visit.push_back((starting_dir, starting_point));
@@ -111,7 +125,7 @@ impl Day16 {
             let i = point.0 as usize;
             let j = point.1 as usize;
             for next_direction in direction
-                .get_new_directions(self.grid[i][j])
+                .get_new_directions(Tile::from_byte(self.grid[i][j]).expect("checked by parse"))
                 .iter()
                 .flatten()
             {
@@ -133,9 +147,9 @@ impl Day16 {
     }
 }
 
-impl Executor for Day16 {
-    fn parse(&mut self, input: String) {
-        let _input = r".|...\....
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: r".|...\....
 |.-.\.....
 .....|-...
 ........|.
@@ -144,20 +158,32 @@ impl Executor for Day16 {
 ..../.\\..
 .-.-/..|..
 .|....-|.\
-..//.|....";
-        for line in input.lines() {
-            self.grid.push(line.chars().map(Tile::from).collect());
+..//.|....
+",
+        part_one: Some("46"),
+        part_two: Some("51"),
+    },
+];
+
+impl<'a> Executor<'a> for Day16<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        for (i, line) in grid_within(input, MAX_SIZE, MAX_SIZE)?.into_iter().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                Tile::try_from(c).map_err(|c| ParseError::unexpected_char(i, j, c))?;
+            }
+            self.grid.push(line.as_bytes());
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let mut to_visit = VecDeque::default();
         let out = self.get_num_energized(Point(0, 0), Direction::East, &mut to_visit);
 
-        _ = write!(output_buffer, "P1: {}", out)
+        out.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let max_out = (0..self.grid.len())
             .flat_map(|i| {
                 [
@@ -185,6 +211,10 @@ impl Executor for Day16 {
             .max()
             .unwrap();
 
-        _ = write!(output_buffer, "P2: {max_out}");
+        max_out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,13 +1,17 @@
-use super::Executor;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid_within;
 use crate::utils::direction::Direction;
 use crate::utils::point::*;
 
 use std::collections::VecDeque;
-use std::fmt::Write;
+
+/// The widest and tallest heat map the visited states have room for
+const MAX_SIZE: usize = 256;
 
 #[derive(Default)]
-pub struct Day17 {
-    heat_map: Vec<Vec<u8>>,
+pub struct Day17<'a> {
+    /// Rows of ASCII digits, straight from the input
+    heat_map: Vec<&'a [u8]>,
 }
 
 impl Direction {
@@ -19,10 +23,10 @@ impl Direction {
     }
 }
 
-impl Day17 {
+impl Day17<'_> {
     fn get_min_cost(&self, turning_scheme: TurningScheme) -> u32 {
         let mut to_visit = BucketQueue::new();
-        let mut visited = [[[0; 16]; 256]; 256];
+        let mut visited = [[[0; 16]; MAX_SIZE]; MAX_SIZE];
         let target = Point(
             self.heat_map.len() as i32 - 1,
             self.heat_map[0].len() as i32 - 1,
@@ -85,7 +89,7 @@ impl Day17 {
                 }
 
                 let next_cost =
-                    cost + self.heat_map[next_location.0 as usize][next_location.1 as usize] as u32;
+                    cost + (self.heat_map[next_location.0 as usize][next_location.1 as usize] - b'0') as u32;
 
                 visited[next_location.0 as usize][next_location.1 as usize]
                     [next_streak as usize] |= next_direction.visit_number();
@@ -149,25 +153,62 @@ impl Ord for TraversalState {
     }
 }
 
-impl Executor for Day17 {
-    fn parse(&mut self, input: String) {
-        for line in input.lines() {
-            self.heat_map.push(
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as u8)
-                    .collect(),
-            )
+const EXAMPLES: [Example; 2] = [
+    Example {
+        input: "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+",
+        part_one: Some("102"),
+        part_two: Some("94"),
+    },
+    Example {
+        input: "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+",
+        part_one: None,
+        part_two: Some("71"),
+    },
+];
+
+impl<'a> Executor<'a> for Day17<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        for (i, line) in grid_within(input, MAX_SIZE, MAX_SIZE)?.into_iter().enumerate() {
+            if let Some((j, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+                return Err(ParseError::unexpected_char(i, j, c));
+            }
+            self.heat_map.push(line.as_bytes());
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let target_distance = self.get_min_cost(TurningScheme::Crucible);
-        _ = write!(output_buffer, "P1: {target_distance}");
+        target_distance.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let target_distance = self.get_min_cost(TurningScheme::UltraCrucible);
-        _ = write!(output_buffer, "P2: {target_distance}");
+        target_distance.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
 
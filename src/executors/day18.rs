@@ -1,15 +1,16 @@
 use nom::{
-    bytes::complete::tag,
-    character::complete::{hex_digit1, one_of},
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::one_of,
     character::complete::{space1, u8},
+    combinator::map_res,
+    error::context,
     sequence::{delimited, preceded, tuple},
-    IResult,
 };
 
-use crate::utils::{direction::Direction, point::Point};
+use crate::utils::direction::Direction;
+use crate::utils::input::lines;
 
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, NomResult, ParseError};
 
 #[derive(Debug, Clone, Copy)]
 struct Instruction {
@@ -20,13 +21,24 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn parse(input: &str) -> IResult<&str, Instruction> {
-        let (input, (raw_direction, count, raw_color)) = tuple((
-            one_of("LRUD"),
-            preceded(space1, u8),
+    fn parse(input: &str) -> NomResult<'_, Instruction> {
+        let hex_count = context(
+            "five hex digits of distance",
+            map_res(
+                take_while_m_n(5, 5, |c: char| c.is_ascii_hexdigit()),
+                |raw| u32::from_str_radix(raw, 16),
+            ),
+        );
+        let direction_digit = context("direction digit", one_of("0123"));
+        let (input, (raw_direction, count, (p2_count, raw_p2_direction))) = tuple((
+            context("direction", one_of("LRUD")),
+            preceded(space1, context("step count", u8)),
             preceded(
                 space1,
-                delimited(tag("("), preceded(tag("#"), hex_digit1), tag(")")),
+                context(
+                    "color",
+                    delimited(tag("(#"), tuple((hex_count, direction_digit)), tag(")")),
+                ),
             ),
         ))(input)?;
         let direction = match raw_direction {
@@ -36,12 +48,11 @@ impl Instruction {
             'D' => Direction::South,
             _ => unreachable!(),
         };
-        let p2_count = u32::from_str_radix(&raw_color[..5], 16).unwrap();
-        let p2_direction = match &raw_color.as_bytes()[5] {
-            b'0' => Direction::East,
-            b'1' => Direction::South,
-            b'2' => Direction::West,
-            b'3' => Direction::North,
+        let p2_direction = match raw_p2_direction {
+            '0' => Direction::East,
+            '1' => Direction::South,
+            '2' => Direction::West,
+            '3' => Direction::North,
             _ => unreachable!(),
         };
         Ok((
@@ -61,14 +72,39 @@ pub struct Day18 {
     instructions: Vec<Instruction>,
 }
 
-impl Executor for Day18 {
-    fn parse(&mut self, input: String) {
-        for line in input.lines() {
-            self.instructions.push(Instruction::parse(line).unwrap().1);
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+",
+        part_one: Some("62"),
+        part_two: Some("952408144115"),
+    },
+];
+
+impl Executor<'_> for Day18 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        for (_, line) in lines(input) {
+            let instruction = ParseError::finish_nom(input, Instruction::parse(line))?;
+            self.instructions.push(instruction);
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let vertices = self
             .instructions
             .iter()
@@ -80,10 +116,10 @@ impl Executor for Day18 {
             });
 
         let total_area = compute_area(std::iter::once((0, 0)).chain(vertices));
-        _ = write!(output_buffer, "P1: {}", total_area);
+        total_area.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let vertices = self
             .instructions
             .iter()
@@ -95,7 +131,11 @@ impl Executor for Day18 {
             });
 
         let total_area = compute_area(std::iter::once((0, 0)).chain(vertices));
-        _ = write!(output_buffer, "P2: {}", total_area);
+        total_area.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
 
@@ -111,6 +151,5 @@ fn compute_area(mut vertices: impl Iterator<Item = (i32, i32)>) -> i64 {
         prev = v2;
     }
     shoelace_area /= 2;
-    let total_area = shoelace_area.abs() + ((perimeter_area / 2) + 1);
-    total_area
+    shoelace_area.abs() + ((perimeter_area / 2) + 1)
 }
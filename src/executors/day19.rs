@@ -1,13 +1,14 @@
 use nom::{
     bytes::complete::{tag, take_until},
     character::complete::{one_of, u64},
+    error::context,
     sequence::{delimited, preceded, separated_pair, terminated, tuple},
-    IResult,
 };
 use rustc_hash::FxHashMap;
 
-use super::Executor;
-use std::{fmt::Write, ops::RangeInclusive};
+use super::{failure, Answer, Example, Executor, NomError, NomResult, ParseError};
+use crate::utils::input::{lines, sections};
+use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone, Copy)]
 enum Category {
@@ -83,24 +84,36 @@ impl Workflow {
     }
 }
 
+/// Fails without backtracking when `destination` names a workflow that
+/// doesn't exist
+fn resolve_destination<'a>(
+    destination: &'a str,
+    labels: &FxHashMap<&'a str, usize>,
+) -> Result<Verdict, nom::Err<NomError<'a>>> {
+    match destination {
+        "A" => Ok(Verdict::Accept),
+        "R" => Ok(Verdict::Reject),
+        other => labels
+            .get(other)
+            .map(|&i| Verdict::RouteTo(i))
+            .ok_or_else(|| failure(destination, "unknown workflow")),
+    }
+}
+
 fn parse_workstream<'a>(
     input: &'a str,
     labels: &'a FxHashMap<&'a str, usize>,
-) -> IResult<&'a str, (char, Criteria, Verdict)> {
+) -> NomResult<'a, (char, Criteria, Verdict)> {
     // X -> M -> A -> S -> Final
     let (input, ((ws_category, crit_symbol, comparator), destination)) = terminated(
         separated_pair(
-            tuple((one_of("xmas"), one_of("><"), u64)),
+            context("condition", tuple((one_of("xmas"), one_of("><"), u64))),
             tag(":"),
             take_until(","),
         ),
         tag(","),
     )(input)?;
-    let verdict = match destination {
-        "A" => Verdict::Accept,
-        "R" => Verdict::Reject,
-        other => Verdict::RouteTo(labels[other]),
-    };
+    let verdict = resolve_destination(destination, labels)?;
     let criteria = match crit_symbol {
         '>' => Criteria::GreaterThan(comparator),
         '<' => Criteria::LessThan(comparator),
@@ -112,15 +125,24 @@ fn parse_workstream<'a>(
 fn parse_workflow<'a>(
     input: &'a str,
     label_map: &'a FxHashMap<&'a str, usize>,
-) -> IResult<&'a str, Workflow> {
+) -> NomResult<'a, Workflow> {
     let (input, mut ws) = preceded(
-        take_until("{"),
-        delimited(tag("{"), take_until("}"), tag("}")),
+        context("workflow name", take_until("{")),
+        context("workflow rules", delimited(tag("{"), take_until("}"), tag("}"))),
     )(input)?;
 
     let mut workstreams = [None; 4];
     let mut idx = 0;
-    while let Ok((i, (category, criteria, verdict))) = parse_workstream(ws, label_map) {
+    loop {
+        let rule = context("workflow rule", |i| parse_workstream(i, label_map))(ws);
+        let (i, (category, criteria, verdict)) = match rule {
+            Ok(parsed) => parsed,
+            Err(nom::Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        };
+        if idx == workstreams.len() {
+            return Err(failure(ws, "too many rules in the workflow"));
+        }
         let category = match category {
             'x' => Category::X,
             'm' => Category::M,
@@ -133,11 +155,7 @@ fn parse_workflow<'a>(
         ws = i;
     }
 
-    let workflow_verdict = match ws {
-        "A" => Verdict::Accept,
-        "R" => Verdict::Reject,
-        other => Verdict::RouteTo(label_map[other]),
-    };
+    let workflow_verdict = resolve_destination(ws, label_map)?;
 
     let workflow = Workflow {
         workstreams,
@@ -147,13 +165,16 @@ fn parse_workflow<'a>(
     Ok((input, workflow))
 }
 
-fn parse_part(input: &str) -> IResult<&str, Part> {
-    let (input, (x, m, a, s)) = tuple((
-        delimited(tag("{x="), u64, tag(",")),
-        delimited(tag("m="), u64, tag(",")),
-        delimited(tag("a="), u64, tag(",")),
-        delimited(tag("s="), u64, tag("}")),
-    ))(input)?;
+fn parse_part(input: &str) -> NomResult<'_, Part> {
+    let (input, (x, m, a, s)) = context(
+        "part ratings",
+        tuple((
+            delimited(tag("{x="), u64, tag(",")),
+            delimited(tag("m="), u64, tag(",")),
+            delimited(tag("a="), u64, tag(",")),
+            delimited(tag("s="), u64, tag("}")),
+        )),
+    )(input)?;
 
     Ok((input, Part { x, m, a, s }))
 }
@@ -220,27 +241,59 @@ impl Day19 {
     }
 }
 
-impl Executor for Day19 {
-    fn parse(&mut self, input: String) {
-        let mut groups = input.split("\n\n");
-        let rules = groups.next().unwrap();
-        let parts = groups.next().unwrap();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+",
+        part_one: Some("19114"),
+        part_two: Some("167409079868000"),
+    },
+];
+
+impl Executor<'_> for Day19 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let mut groups = sections(input);
+        let rules = groups.next().unwrap_or_default();
+        let Some(parts) = groups.next() else {
+            return Err(ParseError::at(input, &input[input.len()..], "missing the part ratings"));
+        };
         let mut label_hash = FxHashMap::default();
-        for (i, line) in rules.lines().enumerate() {
-            let label = line.split('{').next().unwrap();
+        for (i, line) in lines(rules) {
+            let label = line.split('{').next().unwrap_or_default();
             label_hash.insert(label, i);
         }
-        for line in rules.lines() {
-            self.workflows
-                .push(parse_workflow(line, &label_hash).unwrap().1);
+        for (_, line) in lines(rules) {
+            let workflow = ParseError::finish_nom(input, parse_workflow(line, &label_hash))?;
+            self.workflows.push(workflow);
         }
-        for line in parts.lines() {
-            self.parts.push(parse_part(line).unwrap().1);
+        for (_, line) in lines(parts) {
+            self.parts.push(ParseError::finish_nom(input, parse_part(line))?);
         }
-        self.start = label_hash["in"];
+        let Some(&start) = label_hash.get("in") else {
+            return Err(ParseError::at(input, rules, "no workflow named \"in\""));
+        };
+        self.start = start;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let mut accepted_total = 0u64;
         for part in &self.parts {
             let mut workflow = self.start;
@@ -254,14 +307,19 @@ impl Executor for Day19 {
                 accepted_total += part.x + part.m + part.a + part.s
             }
         }
-        _ = write!(output_buffer, "P1: {accepted_total}");
+        accepted_total.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let range_part = RangePart {
             ratings: std::array::from_fn(|_| 1..=4000),
         };
         let out = self.evaluate_part_range(range_part);
-        _ = write!(output_buffer, "P2: {out}");
+        out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
+
@@ -1,20 +1,19 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, NomResult, ParseError};
+use crate::utils::input::lines;
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete as cc,
     combinator::opt,
+    error::context,
     multi::separated_list1,
     sequence::{delimited, separated_pair, terminated},
-    Finish, IResult,
 };
 
 #[derive(Default)]
 pub struct Day2 {
     games: Vec<Game>,
-    p2_sum: i32,
 }
 
 #[derive(Debug)]
@@ -24,26 +23,40 @@ struct Game {
 }
 
 impl Game {
-    fn parse(input: &str) -> IResult<&str, Game> {
-        let (input, id) = delimited(tag("Game "), cc::i32, tag(": "))(input)?;
-        let (input, samples) = separated_list1(tag("; "), Sample::parse)(input)?;
+    fn parse(input: &str) -> NomResult<'_, Game> {
+        let (input, id) = context("game id", delimited(tag("Game "), cc::i32, tag(": ")))(input)?;
+        let (input, samples) =
+            separated_list1(tag("; "), context("sample", Sample::parse))(input)?;
         Ok((input, Game { id, samples }))
     }
+
+    fn maxima(&self) -> (i32, i32, i32) {
+        let mut max_blue = 0;
+        let mut max_green = 0;
+        let mut max_red = 0;
+
+        for &Sample(r, g, b) in &self.samples {
+            max_blue = std::cmp::max(max_blue, r);
+            max_red = std::cmp::max(max_red, g);
+            max_green = std::cmp::max(max_green, b);
+        }
+        (max_blue, max_green, max_red)
+    }
 }
 
 #[derive(Debug, Default)]
 struct Sample(i32, i32, i32);
 
 impl Sample {
-    fn parse(input: &str) -> IResult<&str, Sample> {
+    fn parse(input: &str) -> NomResult<'_, Sample> {
         let mut input = input;
         let mut out = Sample(0, 0, 0);
         while !input.starts_with(';') && !input.is_empty() {
             let (s, (count, color)) = terminated(
                 separated_pair(
-                    cc::i32,
+                    context("cube count", cc::i32),
                     cc::space1,
-                    alt((tag("green"), tag("blue"), tag("red"))),
+                    context("cube color", alt((tag("green"), tag("blue"), tag("red")))),
                 ),
                 opt(tag(", ")),
             )(input)?;
@@ -62,37 +75,49 @@ impl Sample {
     }
 }
 
-impl Executor for Day2 {
-    fn parse(&mut self, input: String) {
-        self.games = input
-            .lines()
-            .map(|g| Game::parse(g).finish().unwrap().1)
-            .collect();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+        part_one: Some("8"),
+        part_two: Some("2286"),
+    },
+];
+
+impl Executor<'_> for Day2 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        self.games = lines(input)
+            .map(|(_, g)| ParseError::finish_nom(input, Game::parse(g)))
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let mut p1_sum = 0;
-        let mut p2_sum = 0;
         for game in &self.games {
-            let mut max_blue = 0;
-            let mut max_green = 0;
-            let mut max_red = 0;
-
-            for &Sample(r, g, b) in &game.samples {
-                max_blue = std::cmp::max(max_blue, r);
-                max_red = std::cmp::max(max_red, g);
-                max_green = std::cmp::max(max_green, b);
-            }
+            let (max_blue, max_green, max_red) = game.maxima();
             if max_red <= 12 && max_green <= 13 && max_blue <= 14 {
                 p1_sum += game.id;
             }
+        }
+        p1_sum.into()
+    }
+
+    fn part_two(&self) -> Answer {
+        let mut p2_sum = 0;
+        for game in &self.games {
+            let (max_blue, max_green, max_red) = game.maxima();
             p2_sum += max_red * max_blue * max_green
         }
-        self.p2_sum = p2_sum;
-        _ = write!(output_buffer, "P1: {p1_sum}");
+        p2_sum.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        _ = write!(output_buffer, "P2: {}", self.p2_sum);
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
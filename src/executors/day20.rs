@@ -2,18 +2,20 @@ use nom::{
     bytes::complete::{tag, take_until},
     character::complete::{alpha1, one_of},
     combinator::{opt, rest},
-    multi::fold_many0,
+    error::context,
+    multi::many0,
     sequence::{pair, preceded, terminated, tuple},
-    IResult,
 };
 use rustc_hash::FxHashMap;
 use tinyvec::ArrayVec;
 
-use super::Executor;
+use super::{failure, Answer, Example, Executor, NomError, NomResult, ParseError};
+use crate::utils::input::lines;
 use std::collections::VecDeque;
-use std::fmt::Write;
 
-#[derive(Debug, Default)]
+const MAX_MODULES: usize = u64::BITS as usize;
+
+#[derive(Debug, Default, Clone)]
 struct Arena {
     modules: Vec<Module>,
     signal_queue: VecDeque<Signal>,
@@ -36,21 +38,6 @@ impl Arena {
         }
     }
 
-    fn reset(&mut self) {
-        for module in &mut self.modules {
-            match module {
-                Module::Conjunction(Conjunction { input_signals, .. }) => {
-                    *input_signals = 0;
-                }
-                Module::FlipFlop(FlipFlop { state, .. }) => *state = FlipFlopState::Off,
-                // No reset required for untyped modules
-                _ => {}
-            }
-        }
-        self.module_high_pulse_watcher = None;
-        self.signal_queue.clear();
-    }
-
     fn press_button(&mut self) -> (u32, u32, bool) {
         let Self {
             modules,
@@ -136,10 +123,12 @@ impl Module {
     fn process_signal(&mut self, signal: Signal) -> [Option<Signal>; 8] {
         let mut out = std::array::from_fn(|_| None);
         match self {
-            Module::FlipFlop(FlipFlop { state, outputs }) => match signal {
-                Signal {
+            Module::FlipFlop(FlipFlop { state, outputs }) => {
+                // If we receive a high pulse do nothing
+                if let Signal {
                     pulse: Pulse::Low, ..
-                } => {
+                } = signal
+                {
                     if matches!(state, FlipFlopState::On) {
                         *state = FlipFlopState::Off;
                         for i in 0..outputs.len() {
@@ -160,9 +149,7 @@ impl Module {
                         }
                     }
                 }
-                // If we receive a high pulse do nothing
-                _ => {}
-            },
+            }
             // Conjunction modules (prefix &) remember the type of the most recent pulse received from each of their
             // connected input modules; they initially default to remembering a low pulse for each input.
             // When a pulse is received, the conjunction module first updates its memory for that input.
@@ -210,20 +197,22 @@ impl Module {
         input: &'a str,
         labels: &'a FxHashMap<&'a str, usize>,
         num_modules: usize,
-    ) -> IResult<&'a str, Module> {
+    ) -> NomResult<'a, Module> {
         let (input, (module_type_char, raw_outputs)) = tuple((
-            opt(one_of("%&")),
-            preceded(pair(take_until("-> "), tag("-> ")), rest),
+            context("module type", opt(one_of("%&"))),
+            preceded(context("module name", pair(take_until("-> "), tag("-> "))), rest),
         ))(input)?;
 
-        let (_, outputs) = fold_many0(
-            terminated(alpha1::<&str, _>, opt(tag(", "))),
-            ArrayVec::new,
-            |mut acc, v: &str| {
-                acc.push(*labels.get(v).unwrap_or(&num_modules));
-                acc
-            },
+        let (_, names) = context(
+            "module outputs",
+            many0(terminated(alpha1::<&str, NomError>, opt(tag(", ")))),
         )(raw_outputs)?;
+        let mut outputs = ArrayVec::new();
+        for name in names {
+            if outputs.try_push(*labels.get(name).unwrap_or(&num_modules)).is_some() {
+                return Err(failure(name, "too many outputs"));
+            }
+        }
 
         let module = match module_type_char {
             Some('&') => Module::Conjunction(Conjunction {
@@ -244,7 +233,7 @@ impl Module {
         match self {
             Module::Untyped(Untyped { outputs })
             | Module::FlipFlop(FlipFlop { outputs, .. })
-            | Module::Conjunction(Conjunction { outputs, .. }) => outputs.clone(),
+            | Module::Conjunction(Conjunction { outputs, .. }) => *outputs,
         }
     }
 }
@@ -252,77 +241,125 @@ impl Module {
 #[derive(Default)]
 pub struct Day20 {
     arena: Arena,
+    /// The module that feeds `rx`, which only the real puzzle input has
+    rx_lead: Option<usize>,
 }
 
-impl Executor for Day20 {
-    fn parse(&mut self, input: String) {
+const EXAMPLES: [Example; 2] = [
+    Example {
+        input: "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
+",
+        part_one: Some("32000000"),
+        part_two: Some("no module feeds rx"),
+    },
+    Example {
+        input: "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output
+",
+        part_one: Some("11687500"),
+        part_two: Some("no module feeds rx"),
+    },
+];
+
+impl Executor<'_> for Day20 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
         let mut labels = FxHashMap::default();
         let mut num_modules = 0;
-        for (i, line) in input.lines().enumerate() {
-            let label = line.split_whitespace().next().unwrap();
+        for (i, line) in lines(input) {
+            let Some(label) = line.split_whitespace().next() else {
+                return Err(ParseError::in_line(i, 0, "expected a module"));
+            };
+            // Conjunctions remember their inputs in the bits of a `u64`
+            if i == MAX_MODULES {
+                return Err(ParseError::in_line(
+                    i,
+                    0,
+                    format!("more than {MAX_MODULES} modules"),
+                ));
+            }
             labels.insert(label.trim_start_matches(['%', '&']), i);
             num_modules += 1;
         }
-        for line in input.lines() {
-            self.arena
-                .modules
-                .push(Module::parse(line, &labels, num_modules).unwrap().1);
+        for (i, line) in lines(input) {
+            let module = ParseError::finish_nom(input, Module::parse(line, &labels, num_modules))?;
+            let feeds_rx = line
+                .split_once("-> ")
+                .is_some_and(|(_, outputs)| outputs.split(", ").any(|o| o == "rx"));
+            if feeds_rx {
+                self.rx_lead = Some(i);
+            }
+            self.arena.modules.push(module);
         }
         // Push a sink state for any unused modules
         self.arena.modules.push(Module::Untyped(Untyped {
             outputs: ArrayVec::new(),
         }));
-        self.arena.broadcaster = labels["broadcaster"];
+        let Some(&broadcaster) = labels.get("broadcaster") else {
+            return Err(ParseError::at(input, input, "no broadcaster module"));
+        };
+        self.arena.broadcaster = broadcaster;
         self.arena.terminal = self.arena.modules.len() - 1;
         self.arena.populate();
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
+        let mut arena = self.arena.clone();
         let mut low_total = 0;
         let mut high_total = 0;
         for _ in 0..1000 {
-            let (low, high, _) = self.arena.press_button();
+            let (low, high, _) = arena.press_button();
             low_total += low;
             high_total += high;
         }
         let total = low_total * high_total;
-        _ = write!(output_buffer, "P1: {total}");
+        total.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        let terminal_lead = &self
-            .arena
-            .modules
-            .iter()
-            .position(|m| m.get_outputs().contains(&self.arena.terminal))
-            .unwrap();
+    fn part_two(&self) -> Answer {
+        // The examples have no `rx`, so part two doesn't apply to them
+        let Some(terminal_lead) = &self.rx_lead else {
+            return Answer::from("no module feeds rx");
+        };
         let lead_inputs = &self
             .arena
             .modules
-            .iter_mut()
+            .iter()
             .enumerate()
             .filter(|(_i, m)| m.get_outputs().contains(terminal_lead))
             .map(|(i, _m)| i)
             .collect::<Vec<_>>();
         let mut frequencies = lead_inputs.iter().map(|_| 0u64).collect::<Vec<_>>();
         for i in 0..lead_inputs.len() {
-            self.arena.reset();
+            let mut arena = self.arena.clone();
             let mut count = 0;
             let input = lead_inputs[i];
-            self.arena.module_high_pulse_watcher = Some(input);
+            arena.module_high_pulse_watcher = Some(input);
             frequencies[i] = loop {
-                let (_, _, pulsed_high) = self.arena.press_button();
+                let (_, _, pulsed_high) = arena.press_button();
                 count += 1;
                 if pulsed_high {
                     break count;
                 }
             };
         }
-        let out = frequencies
+        frequencies
             .into_iter()
             .map(u128::from)
             .reduce(num::integer::lcm)
-            .unwrap();
-        _ = write!(output_buffer, "P2 {out}");
+            .map_or(Answer::from("nothing feeds the module in front of rx"), Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -3,15 +3,15 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use crate::utils::direction::*;
 use crate::utils::point::{InBounds, Point};
 
-use super::Executor;
+use super::{Answer, Example, Executor, InputKind, ParseError};
+use crate::utils::input::grid;
 
 use std::collections::VecDeque;
-use std::fmt::Write;
 
-// const P1_STEPS: u8 = 64;
-const P1_STEPS: u8 = 7;
-// const P2_STEPS: u32 = 26_501_365;
-const P2_STEPS: u32 = 7;
+const P1_STEPS: u8 = 64;
+const P1_EXAMPLE_STEPS: u8 = 6;
+const P2_STEPS: u32 = 26_501_365;
+const P2_EXAMPLE_STEPS: u32 = 100;
 
 const DIRECTIONS: [Direction; 4] = [
     Direction::North,
@@ -20,30 +20,60 @@ const DIRECTIONS: [Direction; 4] = [
     Direction::West,
 ];
 
-#[derive(Debug, PartialEq, Eq)]
-enum Tile {
-    Garden,
-    Rock,
+/// The only tile that can't be walked on. The start is a garden plot too.
+const ROCK: u8 = b'#';
+#[derive(Default)]
+pub struct Day21<'a> {
+    /// Rows borrowed straight from the input
+    tiles: Vec<&'a [u8]>,
+    start: (usize, usize),
+    input_kind: InputKind,
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Tile {
-        match c {
-            '#' => Tile::Rock,
-            '.' | 'S' => Tile::Garden,
-            _ => unreachable!(),
+impl Day21<'_> {
+    /// Counts the garden plots reachable in exactly each of `steps` steps when
+    /// the map repeats infinitely in every direction
+    fn count_reachable_tiled(&self, steps: &[u32]) -> Vec<u64> {
+        let height = self.tiles.len() as i32;
+        let width = self.tiles[0].len() as i32;
+        let max_steps = steps.iter().copied().max().unwrap_or_default();
+
+        let start = Point(self.start.0 as i32, self.start.1 as i32);
+        let mut distances = FxHashMap::default();
+        let mut to_visit = VecDeque::new();
+        distances.insert(start, 0);
+        to_visit.push_back((start, 0));
+        while let Some((p, distance)) = to_visit.pop_front() {
+            if distance == max_steps {
+                continue;
+            }
+            for direction in DIRECTIONS {
+                let next_p = p + direction;
+                let tile = self.tiles[next_p.0.rem_euclid(height) as usize]
+                    [next_p.1.rem_euclid(width) as usize];
+                if tile != ROCK && !distances.contains_key(&next_p) {
+                    distances.insert(next_p, distance + 1);
+                    to_visit.push_back((next_p, distance + 1));
+                }
+            }
         }
+
+        // A plot reached in d steps can be revisited every other step after that
+        steps
+            .iter()
+            .map(|&s| {
+                distances
+                    .values()
+                    .filter(|&&d| d <= s && d % 2 == s % 2)
+                    .count() as u64
+            })
+            .collect()
     }
 }
-#[derive(Default)]
-pub struct Day21 {
-    tiles: Vec<Vec<Tile>>,
-    start: (usize, usize),
-}
 
-impl Executor for Day21 {
-    fn parse(&mut self, input: String) {
-        let _input = "...........
+const EXAMPLES: [Example; 1] = [Example {
+    input: "\
+...........
 .....###.#.
 .###.##..#.
 ..#.#...#..
@@ -54,21 +84,38 @@ impl Executor for Day21 {
 .##.#.####.
 .##..##.##.
 ...........
-";
-        for (i, line) in input.lines().enumerate() {
-            let mut row = vec![];
+",
+    part_one: Some("16"),
+    part_two: Some("6536"),
+}];
+
+impl<'a> Executor<'a> for Day21<'a> {
+    fn set_input_kind(&mut self, kind: InputKind) {
+        self.input_kind = kind;
+    }
+
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        let mut start = None;
+        for (i, line) in grid(input)?.into_iter().enumerate() {
             for (j, c) in line.chars().enumerate() {
-                if c == 'S' {
-                    self.start = (i, j);
+                match c {
+                    '#' | '.' => {}
+                    'S' => start = Some((i, j)),
+                    _ => return Err(ParseError::unexpected_char(i, j, c)),
                 }
-                row.push(Tile::from(c));
             }
-            self.tiles.push(row);
+            self.tiles.push(line.as_bytes());
         }
-        dbg!(&self.tiles.len(), &self.tiles[0].len());
+        self.start = start
+            .ok_or_else(|| ParseError::at(input, &input[input.len()..], "no start tile"))?;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
+        let target_steps = match self.input_kind {
+            InputKind::Puzzle => P1_STEPS,
+            InputKind::Example => P1_EXAMPLE_STEPS,
+        };
         let mut to_visit = VecDeque::new();
         let mut visited = FxHashSet::default();
 
@@ -77,8 +124,7 @@ impl Executor for Day21 {
         to_visit.push_back(start);
         visited.insert(start);
         while let Some((p, steps)) = to_visit.pop_back() {
-            if steps == P1_STEPS {
-                // println!("{p:?}");
+            if steps == target_steps {
                 count += 1;
                 continue;
             }
@@ -89,7 +135,7 @@ impl Executor for Day21 {
                 let next = (next_p, next_steps);
                 if self.tiles.is_in_bounds(next_p)
                     && !visited.contains(&next)
-                    && self.tiles[next_p.0 as usize][next_p.1 as usize] != Tile::Rock
+                    && self.tiles[next_p.0 as usize][next_p.1 as usize] != ROCK
                 {
                     visited.insert(next);
                     to_visit.push_back(next);
@@ -97,52 +143,32 @@ impl Executor for Day21 {
             }
         }
 
-        _ = write!(output_buffer, "P1: {count:?}");
+        count.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        let mut currently_visiting = FxHashMap::default();
-        let mut to_visit = FxHashMap::default();
-        let mut buffer = vec![];
-
-        let start = Point(self.start.0 as i32, self.start.1 as i32);
-        // let mut count = 0;
-        currently_visiting.insert(start, 1);
-        for _i in 0..P2_STEPS {
-            for (point, multiples) in currently_visiting.drain() {
-                for direction in DIRECTIONS {
-                    let next_p = point + direction;
-                    let mapped = Point(
-                        next_p.0.rem_euclid(self.tiles.len() as i32 - 1),
-                        next_p.1.rem_euclid(self.tiles[0].len() as i32 - 1),
-                    );
-                    if !to_visit.contains_key(&next_p)
-                        && self.tiles[mapped.0 as usize][mapped.1 as usize] != Tile::Rock
-                    {
-                        to_visit.insert(next_p, multiples);
-                    }
-                }
-            }
-            for (k, v) in to_visit.drain() {
-                let mapped = Point(
-                    k.0.rem_euclid(self.tiles.len() as i32 - 1),
-                    k.1.rem_euclid(self.tiles[0].len() as i32 - 1),
-                );
-                buffer.push((mapped, v));
-            }
-            for (mapped_point, multiples) in buffer.drain(..) {
-                to_visit
-                    .entry(mapped_point)
-                    .and_modify(|v| *v += multiples)
-                    .or_insert(multiples);
+    fn part_two(&self) -> Answer {
+        let out = match self.input_kind {
+            InputKind::Example => self.count_reachable_tiled(&[P2_EXAMPLE_STEPS])[0],
+            InputKind::Puzzle => {
+                // The puzzle map is square with clear lanes out from the start,
+                // so every map width of extra steps grows the reachable area by
+                // a fixed quadratic. Sample three points and extrapolate.
+                let size = self.tiles.len() as u32;
+                let remainder = P2_STEPS % size;
+                let samples = self.count_reachable_tiled(&[
+                    remainder,
+                    remainder + size,
+                    remainder + 2 * size,
+                ]);
+                let [a0, a1, a2] = [samples[0], samples[1], samples[2]].map(|v| v as i64);
+                let n = (P2_STEPS / size) as i64;
+                (a0 + n * (a1 - a0) + n * (n - 1) / 2 * (a2 - 2 * a1 + a0)) as u64
             }
-            std::mem::swap(&mut to_visit, &mut currently_visiting);
-        }
-        // println!(&currently_visiting, &to_visit);
-        // for p in &currently_visiting {
-        //     println!("{p:?}");
-        // }
-        let s = currently_visiting.values().sum::<u64>();
-        _ = write!(output_buffer, "P2: {s:?}");
+        };
+        out.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
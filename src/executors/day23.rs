@@ -1,19 +1,22 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Executor, ParseError};
 
 #[derive(Default)]
 pub struct Day23;
 
-impl Executor for Day23 {
-    fn parse(&mut self, _input: String) {
-        println!("Not implemented");
+impl Executor<'_> for Day23 {
+    fn parse(&mut self, _input: &str) -> Result<(), ParseError> {
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        _ = writeln!(output_buffer, "Not implemented");
+    fn part_one(&self) -> Answer {
+        Answer::NotImplemented
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        _ = writeln!(output_buffer, "Not implemented");
+    fn part_two(&self) -> Answer {
+        Answer::NotImplemented
+    }
+
+    fn is_implemented(&self) -> bool {
+        false
     }
 }
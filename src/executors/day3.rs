@@ -1,17 +1,44 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::grid;
 
 use rustc_hash::FxHashMap;
 
 #[derive(Default)]
-pub struct Day3 {
+pub struct Day3<'a> {
     part_costs: FxHashMap<(usize, usize), Vec<u32>>,
-    lines: Vec<Vec<char>>,
+    lines: Vec<&'a [u8]>,
 }
 
-impl Executor for Day3 {
-    fn parse(&mut self, input: String) {
-        let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+",
+        part_one: Some("4361"),
+        part_two: Some("467835"),
+    },
+];
+
+impl<'a> Executor<'a> for Day3<'a> {
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError> {
+        let rows = grid(input)?;
+        // Rows are walked as bytes, which only line up when every character
+        // takes one
+        for (i, row) in rows.iter().enumerate() {
+            if let Some((j, c)) = row.chars().enumerate().find(|(_, c)| !c.is_ascii()) {
+                return Err(ParseError::unexpected_char(i, j, c));
+            }
+        }
+        let lines: Vec<&[u8]> = rows.into_iter().map(str::as_bytes).collect();
         let mut current_num = None;
         let mut associated_symbol: Option<(usize, usize)> = None;
         let mut part_costs: FxHashMap<(usize, usize), Vec<u32>> = FxHashMap::default();
@@ -20,7 +47,7 @@ impl Executor for Day3 {
                 if lines[i][j].is_ascii_digit() {
                     let n = current_num.get_or_insert(0);
                     *n *= 10;
-                    *n += lines[i][j].to_digit(10).unwrap();
+                    *n += (lines[i][j] - b'0') as u32;
 
                     let i = i as isize;
                     let j = j as isize;
@@ -46,7 +73,7 @@ impl Executor for Day3 {
                         let next_i = next_i as usize;
                         let next_j = next_j as usize;
                         let neighbor = lines[next_i][next_j];
-                        if !neighbor.is_ascii_digit() && neighbor != '.' {
+                        if !neighbor.is_ascii_digit() && neighbor != b'.' {
                             debug_assert!(
                                 associated_symbol.is_none()
                                     || associated_symbol.unwrap() == (next_i, next_j)
@@ -69,24 +96,29 @@ impl Executor for Day3 {
 
         self.part_costs = part_costs;
         self.lines = lines;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        _ = write!(
-            output_buffer,
-            "P1: {}",
-            self.part_costs.values().flat_map(|v| v.iter()).sum::<u32>()
-        );
+    fn part_one(&self) -> Answer {
+        self.part_costs
+            .values()
+            .flat_map(|v| v.iter())
+            .sum::<u32>()
+            .into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let gear_ratio_sum = self
             .part_costs
             .iter()
-            .filter(|(&(i, j), costs)| self.lines[i][j] == '*' && costs.len() == 2)
+            .filter(|(&(i, j), costs)| self.lines[i][j] == b'*' && costs.len() == 2)
             .map(|(_, c)| c[0] * c[1])
             .sum::<u32>();
 
-        _ = write!(output_buffer, "P2: {}", gear_ratio_sum);
+        gear_ratio_sum.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,14 +1,14 @@
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, NomError, NomResult, ParseError};
+use crate::utils::input::lines;
 
 use nom::{
     bytes::complete::tag,
     bytes::complete::take_until,
     character::complete as cc,
     combinator::iterator,
+    error::context,
     multi::fold_many1,
     sequence::{delimited, preceded, terminated},
-    IResult,
 };
 
 #[derive(Debug)]
@@ -18,29 +18,37 @@ struct Card {
 }
 
 impl Card {
-    fn parse(input: &str) -> IResult<&str, Card> {
-        let (input, _) = terminated(take_until(":"), tag(":"))(input)?;
+    fn parse(input: &str) -> NomResult<'_, Card> {
+        let (input, _) = context("card header", terminated(take_until(":"), tag(":")))(input)?;
 
         let mut winning_numbers = [0u8; 10];
-        let mut winning_numbers_iter = iterator(input, delimited(cc::space0, cc::u8, cc::space0));
-        for (i, num) in winning_numbers_iter.enumerate() {
-            winning_numbers[i] = num;
+        let mut winning_numbers_iter = iterator(
+            input,
+            context::<_, NomError, _, _>(
+                "winning number",
+                delimited(cc::space0, cc::u8, cc::space0),
+            ),
+        );
+        for (slot, num) in winning_numbers.iter_mut().zip(&mut winning_numbers_iter) {
+            *slot = num;
         }
-        let res: IResult<_, _> = winning_numbers_iter.finish();
-        let (input, _) = res.unwrap();
+        let (input, ()) = winning_numbers_iter.finish()?;
 
-        let (input, num_winning) = preceded(
-            tag("|"),
-            fold_many1(
-                preceded(cc::space1, cc::u8),
-                || 0,
-                |acc, val| {
-                    if winning_numbers.contains(&val) {
-                        acc + 1
-                    } else {
-                        acc
-                    }
-                },
+        let (input, num_winning) = context(
+            "numbers you have",
+            preceded(
+                tag("|"),
+                fold_many1(
+                    preceded(cc::space1, cc::u8),
+                    || 0,
+                    |acc, val| {
+                        if winning_numbers.contains(&val) {
+                            acc + 1
+                        } else {
+                            acc
+                        }
+                    },
+                ),
             ),
         )(input)?;
 
@@ -59,12 +67,30 @@ pub struct Day4 {
     cards: Vec<Card>,
 }
 
-impl Executor for Day4 {
-    fn parse(&mut self, input: String) {
-        self.cards = input.lines().map(|l| Card::parse(l).unwrap().1).collect();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+",
+        part_one: Some("13"),
+        part_two: Some("30"),
+    },
+];
+
+impl Executor<'_> for Day4 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        self.cards = lines(input)
+            .map(|(_, l)| ParseError::finish_nom(input, Card::parse(l)))
+            .collect::<Result<_, _>>()?;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let total: usize = self
             .cards
             .iter()
@@ -74,24 +100,25 @@ impl Executor for Day4 {
             })
             .sum();
 
-        _ = write!(output_buffer, "P1: {total}");
+        total.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
+        let mut copies: Vec<_> = self.cards.iter().map(|card| card.copies).collect();
         let mut total = 0;
-        for i in 0..self.cards.len() {
-            let Card {
-                num_winning,
-                copies,
-            } = &self.cards[i];
-            let additional_copies = *copies;
-            let num_winning = *num_winning as usize;
-            total += copies;
-            for Card { copies, .. } in self.cards[(i + 1)..(i + 1) + num_winning].as_mut() {
-                *copies += additional_copies;
+        for (i, card) in self.cards.iter().enumerate() {
+            let additional_copies = copies[i];
+            let num_winning = card.num_winning as usize;
+            total += additional_copies;
+            for card_copies in copies[(i + 1)..(i + 1) + num_winning].iter_mut() {
+                *card_copies += additional_copies;
             }
         }
 
-        _ = write!(output_buffer, "P2: {total}");
+        total.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,4 +1,5 @@
-use super::Executor;
+use super::{Answer, Example, Executor, NomResult, ParseError};
+use crate::utils::input::sections;
 use crate::utils::partitioned_by::*;
 
 use nom::bytes::complete::tag;
@@ -6,13 +7,12 @@ use nom::bytes::complete::take_until;
 use nom::character::complete::line_ending;
 use nom::character::complete::space1;
 use nom::character::complete::u64 as ccu64;
+use nom::error::context;
 use nom::multi::separated_list1;
 use nom::sequence::preceded;
 use nom::sequence::terminated;
 use nom::sequence::tuple;
-use nom::IResult;
 
-use std::fmt::Write;
 use std::ops::Range;
 
 #[derive(Debug)]
@@ -48,9 +48,11 @@ impl Map {
         partitions
     }
 
-    fn parse(input: &str) -> IResult<&str, Map> {
-        let (input, (destination_start, source_start, size)) =
-            tuple((terminated(ccu64, space1), terminated(ccu64, space1), ccu64))(input)?;
+    fn parse(input: &str) -> NomResult<'_, Map> {
+        let (input, (destination_start, source_start, size)) = context(
+            "map range",
+            tuple((terminated(ccu64, space1), terminated(ccu64, space1), ccu64)),
+        )(input)?;
 
         Ok((
             input,
@@ -69,34 +71,81 @@ pub struct Day5 {
     map_cascade: Vec<Vec<Map>>,
 }
 
-fn parse_seeds(input: &str) -> IResult<&str, Vec<u64>> {
-    let (i, seeds) = preceded(tag("seeds: "), separated_list1(space1, ccu64))(input)?;
+fn parse_seeds(input: &str) -> NomResult<'_, Vec<u64>> {
+    let (i, seeds) = context(
+        "seed list",
+        preceded(tag("seeds: "), separated_list1(space1, ccu64)),
+    )(input)?;
     Ok((i, seeds))
 }
 
-fn parse_map_section(input: &str) -> IResult<&str, Vec<Map>> {
-    let (input, _title) = terminated(take_until(" map:\n"), tag(" map:\n"))(input)?;
+fn parse_map_section(input: &str) -> NomResult<'_, Vec<Map>> {
+    let (input, _title) =
+        context("map title", terminated(take_until(" map:\n"), tag(" map:\n")))(input)?;
     let (input, map_section) = separated_list1(line_ending, Map::parse)(input)?;
     Ok((input, map_section))
 }
 
-impl Executor for Day5 {
-    fn parse(&mut self, input: String) {
-        let mut sections = input.split("\n\n");
-        let seeds_section = sections.next().unwrap();
-        let seeds = parse_seeds(seeds_section).unwrap().1;
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+",
+        part_one: Some("35"),
+        part_two: Some("46"),
+    },
+];
+
+impl Executor<'_> for Day5 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let mut sections = sections(input);
+        let seeds_section = sections.next().unwrap_or_default();
+        let seeds = ParseError::finish_nom(input, parse_seeds(seeds_section))?;
 
         let mut map_cascade = vec![];
         for section in sections {
-            let section_map = parse_map_section(section).unwrap().1;
+            let section_map = ParseError::finish_nom(input, parse_map_section(section))?;
             map_cascade.push(section_map);
         }
 
         self.seeds = seeds;
         self.map_cascade = map_cascade;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let mut min_location = u64::MAX;
         for seed in &self.seeds {
             let mut v = *seed;
@@ -112,10 +161,10 @@ impl Executor for Day5 {
             min_location = std::cmp::min(min_location, v);
         }
 
-        _ = write!(output_buffer, "P1: {min_location}");
+        min_location.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let mut inputs: Vec<_> = self
             .seeds
             .chunks_exact(2)
@@ -131,9 +180,9 @@ impl Executor for Day5 {
                         middle,
                         upper,
                     } = map.map_range(range);
-                    buffer.extend(lower.into_iter());
-                    buffer.extend(upper.into_iter());
-                    outputs.extend(middle.into_iter());
+                    buffer.extend(lower);
+                    buffer.extend(upper);
+                    outputs.extend(middle);
                 }
                 std::mem::swap(&mut inputs, &mut buffer);
             }
@@ -145,6 +194,10 @@ impl Executor for Day5 {
         let min_location = outputs
             .iter()
             .fold(u64::MAX, |acc, rng| std::cmp::min(acc, rng.start));
-        _ = write!(output_buffer, "P2: {min_location}");
+        min_location.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
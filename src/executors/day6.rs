@@ -1,15 +1,12 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{newline, space1, u64},
-    combinator::opt,
-    multi::fold_many1,
-    sequence::{delimited, preceded},
-    IResult,
+    character::complete::{space1, u64},
+    error::context,
+    multi::fold_many_m_n,
+    sequence::preceded,
 };
 
-use super::Executor;
-
-use std::fmt::Write;
+use super::{failure, Answer, Example, Executor, NomError, NomResult, ParseError};
 
 #[derive(Default, Debug)]
 pub struct Day6 {
@@ -19,12 +16,15 @@ pub struct Day6 {
 
 fn parse_vals<'a>(
     input: &'a str,
-    prefix: &'a str,
-    slice: &'a mut [Option<u64>],
-) -> IResult<&'a str, ()> {
-    let (input, _) = delimited(
+    prefix: &str,
+    slice: &mut [Option<u64>],
+) -> NomResult<'a, ()> {
+    let capacity = slice.len();
+    let (input, _) = preceded(
         tag(prefix),
-        fold_many1(
+        fold_many_m_n(
+            1,
+            capacity,
             preceded(space1, u64),
             || 0,
             |acc, val| {
@@ -32,12 +32,20 @@ fn parse_vals<'a>(
                 acc + 1
             },
         ),
-        opt(newline),
     )(input)?;
-    Ok((input, ()))
+    match input.strip_prefix('\n') {
+        Some(rest) => Ok((rest, ())),
+        None if input.is_empty() => Ok((input, ())),
+        None if preceded(space1::<_, NomError>, u64)(input).is_ok() => {
+            Err(failure(input, "more than four races"))
+        }
+        None => Err(failure(input, "expected the end of the line")),
+    }
 }
 
-fn get_num_winning_charge_times(available_time: u64, record_distance: u64) -> u64 {
+/// Part two runs one race as long as all of them joined up, which can take
+/// more than a `u64` to time
+fn get_num_winning_charge_times(available_time: u128, record_distance: u128) -> u128 {
     let mut lower_bound = 0;
     let mut upper_bound = available_time / 2;
 
@@ -72,38 +80,57 @@ fn get_num_winning_charge_times(available_time: u64, record_distance: u64) -> u6
     upper - lower
 }
 
-impl Executor for Day6 {
-    fn parse(&mut self, input: String) {
-        let (input, _) = parse_vals(&input, "Time:", &mut self.times).unwrap();
-        let _ = parse_vals(input, "Distance:", &mut self.distances).unwrap();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+Time:      7  15   30
+Distance:  9  40  200
+",
+        part_one: Some("288"),
+        part_two: Some("71503"),
+    },
+];
+
+impl Executor<'_> for Day6 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let (rest, _) = context("race times", |i| parse_vals(i, "Time:", &mut self.times))(input)
+            .map_err(|e| ParseError::from_nom(input, e))?;
+        let distances = context("record distances", |i| {
+            parse_vals(i, "Distance:", &mut self.distances)
+        })(rest);
+        ParseError::finish_nom(input, distances)
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let total = self
             .times
             .iter()
             .flatten()
             .zip(self.distances.iter().flatten())
             .fold(1, |acc, (time, distance)| {
-                acc * get_num_winning_charge_times(*time, *distance)
+                acc * get_num_winning_charge_times(*time as u128, *distance as u128)
             });
 
-        _ = write!(output_buffer, "P1: {total}");
+        total.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let time = self
             .times
             .iter()
             .flatten()
-            .fold(0, |acc, val| acc * 10u64.pow(val.ilog10() + 1) + val);
+            .fold(0, |acc, &val| acc * 10u128.pow(val.ilog10() + 1) + val as u128);
 
         let distance = self
             .distances
             .iter()
             .flatten()
-            .fold(0, |acc, val| acc * 10u64.pow(val.ilog10() + 1) + val);
+            .fold(0, |acc, &val| acc * 10u128.pow(val.ilog10() + 1) + val as u128);
         let num_record_breaking_runs = get_num_winning_charge_times(time, distance);
-        _ = write!(output_buffer, "P2: {num_record_breaking_runs}");
+        num_record_breaking_runs.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,9 +1,9 @@
-use super::Executor;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::lines;
 
 use std::cmp::PartialOrd;
 use std::mem::MaybeUninit;
 
-use std::fmt::Write;
 #[derive(Debug, PartialEq, PartialOrd, Copy, Clone, Eq, Ord)]
 enum Card {
     Joker,
@@ -15,14 +15,15 @@ enum Card {
 }
 
 impl Card {
-    fn from_char(c: char) -> Card {
+    fn from_char(c: char) -> Option<Card> {
         match c {
-            'A' => Card::Ace,
-            'K' => Card::King,
-            'Q' => Card::Queen,
-            'J' => Card::Jack,
-            'T' => Card::Number(10),
-            _ => Card::Number(c.to_digit(10).unwrap() as u8),
+            'A' => Some(Card::Ace),
+            'K' => Some(Card::King),
+            'Q' => Some(Card::Queen),
+            'J' => Some(Card::Jack),
+            'T' => Some(Card::Number(10)),
+            '2'..='9' => c.to_digit(10).map(|n| Card::Number(n as u8)),
+            _ => None,
         }
     }
 
@@ -56,9 +57,10 @@ impl HandType {
             counts[card.get_order_key() as usize] += 1;
         }
         let jokers = counts[0];
-        let max_index = counts[1..]
+        let max_index = counts
             .iter()
             .enumerate()
+            .skip(1)
             .fold((1, 0), |(max_i, max_count), (i, count)| {
                 if *count >= max_count {
                     (i, *count)
@@ -101,53 +103,77 @@ pub struct Day7 {
     hands: Vec<(Hand, u32)>,
 }
 
-impl Day7 {
-    fn compute_total_winnings(&self) -> u32 {
-        self.hands
-            .iter()
-            .enumerate()
-            .fold(0, |acc, (i, (_h, bet))| acc + ((i as u32 + 1) * bet))
-    }
+/// Ranks the hands in place, then totals each bet multiplied by its rank
+fn compute_total_winnings(hands: &mut [(Hand, u32)]) -> u32 {
+    hands.sort_unstable();
+    hands
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, (_h, bet))| acc + ((i as u32 + 1) * bet))
 }
 
-impl Executor for Day7 {
-    fn parse(&mut self, input: String) {
-        for line in input.lines() {
-            let mut parts = line.split(' ');
-            let raw_hand = parts.next().unwrap();
-            let raw_bid = parts.next().unwrap();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+",
+        part_one: Some("6440"),
+        part_two: Some("5905"),
+    },
+];
+
+impl Executor<'_> for Day7 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        for (i, line) in lines(input) {
+            let Some((raw_hand, raw_bid)) = line.split_once(' ') else {
+                return Err(ParseError::in_line(i, line.len(), "expected a space before the bid"));
+            };
+            if raw_hand.len() != 5 {
+                return Err(ParseError::in_line(i, 0, "a hand should have five cards"));
+            }
 
             let mut cards: [MaybeUninit<Card>; 5] =
                 unsafe { std::mem::MaybeUninit::uninit().assume_init() };
 
-            for (i, c) in raw_hand.chars().enumerate() {
-                cards[i].write(Card::from_char(c));
+            for (j, c) in raw_hand.chars().enumerate() {
+                let card = Card::from_char(c).ok_or_else(|| ParseError::unexpected_char(i, j, c))?;
+                cards[j].write(card);
             }
 
-            let cards = unsafe { std::mem::transmute::<_, [Card; 5]>(cards) };
+            let cards = unsafe { std::mem::transmute::<[MaybeUninit<Card>; 5], [Card; 5]>(cards) };
 
             let mut bid = 0u32;
-            for c in raw_bid.chars() {
+            for (j, c) in raw_bid.chars().enumerate() {
+                let Some(digit) = c.to_digit(10) else {
+                    return Err(ParseError::unexpected_char(i, raw_hand.len() + 1 + j, c));
+                };
                 bid *= 10;
-                bid += c.to_digit(10).unwrap();
+                bid += digit;
             }
             self.hands
                 .push((Hand(HandType::from_cards(&cards), cards), bid));
         }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        self.hands.sort_unstable();
-
-        _ = write!(output_buffer, "P1: {}", self.compute_total_winnings());
+    fn part_one(&self) -> Answer {
+        compute_total_winnings(&mut self.hands.clone()).into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        self.hands
+    fn part_two(&self) -> Answer {
+        let mut hands = self.hands.clone();
+        hands
             .iter_mut()
             .for_each(|(hand, _)| hand.with_jacks_as_jokers());
 
-        self.hands.sort_unstable();
-        _ = write!(output_buffer, "P2: {}", self.compute_total_winnings());
+        compute_total_winnings(&mut hands).into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
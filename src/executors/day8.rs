@@ -1,13 +1,13 @@
 use rustc_hash::FxHashMap;
 
-use super::Executor;
-use std::fmt::Write;
+use super::{Answer, Example, Executor, NomResult, ParseError};
+use crate::utils::input::{lines, sections};
 
 use nom::{
     bytes::complete::tag,
     character::complete::alphanumeric1,
+    error::context,
     sequence::{delimited, separated_pair, terminated, tuple},
-    IResult,
 };
 
 #[derive(Debug)]
@@ -17,11 +17,11 @@ enum Direction {
 }
 
 impl Direction {
-    fn from_char(c: char) -> Direction {
+    fn from_char(c: char) -> Option<Direction> {
         match c {
-            'R' => Direction::Right,
-            'L' => Direction::Left,
-            _ => unsafe { std::hint::unreachable_unchecked() },
+            'R' => Some(Direction::Right),
+            'L' => Some(Direction::Left),
+            _ => None,
         }
     }
 }
@@ -36,35 +36,84 @@ pub struct Day8 {
     end: usize,
 }
 
-fn parse_node(input: &str) -> IResult<&str, (&str, &str, &str)> {
+fn parse_node(input: &str) -> NomResult<'_, (&str, &str, &str)> {
     let (input, (node, (left, right))) = tuple((
-        terminated(alphanumeric1, tag(" = ")),
-        delimited(
-            tag("("),
-            separated_pair(alphanumeric1, tag(", "), alphanumeric1),
-            tag(")"),
+        context("node label", terminated(alphanumeric1, tag(" = "))),
+        context(
+            "node neighbors",
+            delimited(
+                tag("("),
+                separated_pair(alphanumeric1, tag(", "), alphanumeric1),
+                tag(")"),
+            ),
         ),
     ))(input)?;
 
     Ok((input, (node, left, right)))
 }
 
-impl Executor for Day8 {
-    fn parse(&mut self, input: String) {
-        let mut sections = input.split("\n\n");
-        sections
-            .next()
-            .unwrap()
-            .chars()
-            .for_each(|c| self.directions.push(Direction::from_char(c)));
+const EXAMPLES: [Example; 3] = [
+    Example {
+        input: "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+",
+        part_one: Some("2"),
+        part_two: None,
+    },
+    Example {
+        input: "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+",
+        part_one: Some("6"),
+        part_two: None,
+    },
+    Example {
+        input: "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+",
+        part_one: None,
+        part_two: Some("6"),
+    },
+];
+
+impl Executor<'_> for Day8 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        let mut sections = sections(input);
+        for (j, c) in sections.next().unwrap_or_default().chars().enumerate() {
+            let direction = Direction::from_char(c).ok_or_else(|| ParseError::unexpected_char(0, j, c))?;
+            self.directions.push(direction);
+        }
 
-        let nodes = sections.next().unwrap();
+        let Some(nodes) = sections.next() else {
+            return Err(ParseError::at(input, &input[input.len()..], "missing the node list"));
+        };
         let mut node_vec = vec![];
         let mut node_indexes = FxHashMap::default();
         let mut start = 0;
         let mut end = 0;
-        for (i, line) in nodes.lines().enumerate() {
-            let (_, (node, left, right)) = parse_node(line).unwrap();
+        for (i, line) in lines(nodes) {
+            let (node, left, right) = ParseError::finish_nom(input, parse_node(line))?;
             match node {
                 "AAA" => start = i,
                 "ZZZ" => end = i,
@@ -80,16 +129,22 @@ impl Executor for Day8 {
             node_indexes.insert(node, i);
             node_vec.push((left, right));
         }
-        node_vec.iter().for_each(|(l, r)| {
-            self.map
-                .push((*node_indexes.get(l).unwrap(), *node_indexes.get(r).unwrap()))
-        });
+        let lookup = |label: &str| {
+            node_indexes
+                .get(label)
+                .copied()
+                .ok_or_else(|| ParseError::at(input, label, format!("unknown node {label:?}")))
+        };
+        for (l, r) in node_vec {
+            self.map.push((lookup(l)?, lookup(r)?));
+        }
 
         self.start = start;
         self.end = end;
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
+    fn part_one(&self) -> Answer {
         let Day8 {
             directions,
             map,
@@ -109,10 +164,10 @@ impl Executor for Day8 {
             num_steps += 1;
             direction_index = (direction_index + 1) % directions.len();
         }
-        _ = write!(output_buffer, "P1: {num_steps}");
+        num_steps.into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
+    fn part_two(&self) -> Answer {
         let Day8 {
             directions,
             map,
@@ -125,7 +180,7 @@ impl Executor for Day8 {
             .map(|node| {
                 let mut current_node = *node;
                 let mut path_length = 0u64;
-                let mut direction_index = 1;
+                let mut direction_index = 0;
                 loop {
                     path_length += 1;
 
@@ -141,7 +196,12 @@ impl Executor for Day8 {
                     direction_index = (direction_index + 1) % directions.len();
                 }
             })
-            .product();
-        _ = write!(output_buffer, "P2: {min_path_length}");
+            .reduce(num::integer::lcm)
+            .unwrap_or_default();
+        min_path_length.into()
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
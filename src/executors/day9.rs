@@ -1,13 +1,32 @@
-use super::Executor;
-
-use std::fmt::Write;
+use super::{Answer, Example, Executor, ParseError};
+use crate::utils::input::lines;
 
 #[derive(Default, Debug)]
 pub struct Day9 {
     histories: Vec<Vec<i32>>,
-    value_buffer1: Vec<i32>,
-    value_buffer2: Vec<i32>,
-    first_last_value_buffer: Vec<(i32, i32)>,
+}
+
+impl Day9 {
+    fn extrapolate<F>(&self, aggregator: F) -> i32
+    where
+        F: Fn(i32, (i32, i32)) -> i32,
+    {
+        let mut value_buffer1 = Vec::new();
+        let mut value_buffer2 = Vec::new();
+        let mut first_last_value_buffer = Vec::new();
+        self.histories
+            .iter()
+            .map(|h| {
+                h.clone_into(&mut value_buffer1);
+                produce_sequence_value(
+                    &mut value_buffer1,
+                    &mut value_buffer2,
+                    &mut first_last_value_buffer,
+                    &aggregator,
+                )
+            })
+            .sum()
+    }
 }
 
 fn produce_sequence_value<F>(
@@ -37,48 +56,42 @@ where
     first_last_values.drain(..).rev().fold(0, aggregator)
 }
 
-impl Executor for Day9 {
-    fn parse(&mut self, input: String) {
-        input.lines().for_each(|s| {
-            let history = s.split_whitespace().map(|s| s.parse().unwrap()).collect();
+const EXAMPLES: [Example; 1] = [
+    Example {
+        input: "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+",
+        part_one: Some("114"),
+        part_two: Some("2"),
+    },
+];
+
+impl Executor<'_> for Day9 {
+    fn parse(&mut self, input: &str) -> Result<(), ParseError> {
+        for (_, line) in lines(input) {
+            let history = line
+                .split_whitespace()
+                .map(|s| {
+                    s.parse()
+                        .map_err(|_| ParseError::at(input, s, format!("invalid number {s:?}")))
+                })
+                .collect::<Result<_, _>>()?;
             self.histories.push(history)
-        });
+        }
+        Ok(())
     }
 
-    fn part_one(&mut self, output_buffer: &mut dyn Write) {
-        let aggregator = |acc, (_, last)| acc + last;
-        let v: i32 = self
-            .histories
-            .iter()
-            .map(|h| {
-                h.clone_into(&mut self.value_buffer1);
-                produce_sequence_value(
-                    &mut self.value_buffer1,
-                    &mut self.value_buffer2,
-                    &mut self.first_last_value_buffer,
-                    aggregator,
-                )
-            })
-            .sum();
-
-        _ = write!(output_buffer, "P1: {v}");
+    fn part_one(&self) -> Answer {
+        self.extrapolate(|acc, (_, last)| acc + last).into()
     }
 
-    fn part_two(&mut self, output_buffer: &mut dyn Write) {
-        let aggregator = |acc, (first, _)| first - acc;
-        let v: i32 = self
-            .histories
-            .iter_mut()
-            .map(|h| {
-                produce_sequence_value(
-                    h,
-                    &mut self.value_buffer2,
-                    &mut self.first_last_value_buffer,
-                    aggregator,
-                )
-            })
-            .sum();
+    fn part_two(&self) -> Answer {
+        self.extrapolate(|acc, (first, _)| first - acc).into()
+    }
 
-        _ = write!(output_buffer, "P2: {v}");
+    fn examples(&self) -> &'static [Example] {
+        &EXAMPLES
     }
 }
@@ -1,71 +1,537 @@
 #![cfg_attr(rustfmt, rustfmt_skip)]
-use std::fmt::Write;
-
-pub trait Executor {
-    fn parse(&mut self, input: String);
-    fn part_one(&mut self, output_buffer: &mut dyn Write);
-    fn part_two(&mut self, output_buffer: &mut dyn Write);
-}
-
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
-pub mod day20;
-pub mod day21;
-pub mod day22;
-pub mod day23;
-pub mod day24;
-pub mod day25;
-
-pub fn get_executors() -> Vec<Box<dyn Executor>> {
-    vec![
-        Box::<day1::Day1>::default(),
-        Box::<day2::Day2>::default(),
-        Box::<day3::Day3>::default(),
-        Box::<day4::Day4>::default(),
-        Box::<day5::Day5>::default(),
-        Box::<day6::Day6>::default(),
-        Box::<day7::Day7>::default(),
-        Box::<day8::Day8>::default(),
-        Box::<day9::Day9>::default(),
-        Box::<day10::Day10>::default(),
-        Box::<day11::Day11>::default(),
-        Box::<day12::Day12>::default(),
-        Box::<day13::Day13>::default(),
-        Box::<day14::Day14>::default(),
-        Box::<day15::Day15>::default(),
-        Box::<day16::Day16>::default(),
-        Box::<day17::Day17>::default(),
-        Box::<day18::Day18>::default(),
-        Box::<day19::Day19>::default(),
-        Box::<day20::Day20>::default(),
-        Box::<day21::Day21>::default(),
-        Box::<day22::Day22>::default(),
-        Box::<day23::Day23>::default(),
-        Box::<day24::Day24>::default(),
-        Box::<day25::Day25>::default(),
-    ]
-}
-
-fn foo(
-    bar: usize,
-    baz: usize,
-    whatever:usize,
-    other: usize
-    ) {}
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A sample input from a day's puzzle description along with the answers the
+/// puzzle gives for it. Parts the sample doesn't cover are left as `None`.
+pub struct Example {
+    pub input: &'static str,
+    pub part_one: Option<&'static str>,
+    pub part_two: Option<&'static str>,
+}
+
+/// Whether an executor is solving the real puzzle input or one of the samples
+/// from the puzzle description
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputKind {
+    #[default]
+    Puzzle,
+    Example,
+}
+
+/// The answer to one part of a puzzle. The driver decides how to present it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Text(String),
+    NotImplemented,
+}
+
+impl Answer {
+    /// Whether this answer is the one given as text, such as a recorded answer
+    /// or the answer to an example
+    pub fn matches(&self, expected: &str) -> bool {
+        let expected = expected.trim();
+        match self {
+            Answer::Unsigned(value) => expected.parse() == Ok(*value),
+            Answer::Signed(value) => expected.parse() == Ok(*value),
+            Answer::Text(value) => value == expected,
+            Answer::NotImplemented => false,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Padded so answers line up in tables
+        match self {
+            Answer::Unsigned(value) => f.pad(&value.to_string()),
+            Answer::Signed(value) => f.pad(&value.to_string()),
+            Answer::Text(value) => f.pad(value),
+            Answer::NotImplemented => f.pad("not implemented"),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident: $($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(value: $int) -> Answer {
+                    Answer::$variant(value as _)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Unsigned: u8, u16, u32, u64, u128, usize);
+answer_from!(Signed: i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        Answer::Text(value.to_owned())
+    }
+}
+
+/// Why an input couldn't be parsed, and where. Lines and columns count from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// The grammar elements a nom parser was inside when it failed,
+    /// innermost first
+    pub context: Vec<ParseContext>,
+}
+
+/// A labelled grammar element and where it started
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseContext {
+    pub line: usize,
+    pub column: usize,
+    pub label: &'static str,
+}
+
+/// The error type the nom parsers use. Parsers wrap grammar elements in
+/// `nom::error::context` so a failure says what was being parsed.
+pub type NomError<'a> = nom::error::VerboseError<&'a str>;
+pub type NomResult<'a, O> = nom::IResult<&'a str, O, NomError<'a>>;
+
+/// A failure that stops the whole parse, described by `label`
+fn failure<'a>(input: &'a str, label: &'static str) -> nom::Err<NomError<'a>> {
+    nom::Err::Failure(nom::error::VerboseError {
+        errors: vec![(input, nom::error::VerboseErrorKind::Context(label))],
+    })
+}
+
+/// The line and column of the start of `rest`, which must be a slice of `input`
+fn position(input: &str, rest: &str) -> (usize, usize) {
+    let offset = (rest.as_ptr() as usize).saturating_sub(input.as_ptr() as usize).min(input.len());
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+impl ParseError {
+    /// An error at a character of a line, both given as 0-based indexes as
+    /// they come out of `lines().enumerate()` and `chars().enumerate()`
+    pub fn in_line(line_index: usize, column_index: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: line_index + 1,
+            column: column_index + 1,
+            message: message.into(),
+            context: vec![],
+        }
+    }
+
+    /// An error at the start of `rest`, which must be a slice of `input`
+    pub fn at(input: &str, rest: &str, message: impl Into<String>) -> ParseError {
+        let (line, column) = position(input, rest);
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+            context: vec![],
+        }
+    }
+
+    pub fn unexpected_char(line_index: usize, column_index: usize, c: char) -> ParseError {
+        ParseError::in_line(line_index, column_index, format!("unexpected character {c:?}"))
+    }
+
+    /// Converts an error from a nom parser run over `input`
+    pub fn from_nom(input: &str, error: nom::Err<NomError<'_>>) -> ParseError {
+        use nom::error::VerboseErrorKind;
+
+        let errors = match error {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.errors,
+            nom::Err::Incomplete(_) => vec![],
+        };
+        let Some(((rest, kind), outer)) = errors.split_first() else {
+            return ParseError::at(input, &input[input.len()..], "unexpected end of input");
+        };
+        let found = rest.chars().next();
+        let message = match (kind, found) {
+            // Parsers that reject a value they did parse fail with just a label
+            (VerboseErrorKind::Context(label), _) => label.to_string(),
+            (VerboseErrorKind::Char(c), Some(found)) => format!("expected {c:?}, found {found:?}"),
+            (VerboseErrorKind::Char(c), None) => format!("expected {c:?}, found end of input"),
+            (VerboseErrorKind::Nom(code), Some(found)) => format!("unexpected {found:?} ({})", code.description()),
+            (VerboseErrorKind::Nom(_), None) => "unexpected end of input".to_owned(),
+        };
+        let mut parse_error = ParseError::at(input, rest, message);
+        parse_error.context = outer
+            .iter()
+            .filter_map(|(rest, kind)| match kind {
+                VerboseErrorKind::Context(label) => {
+                    let (line, column) = position(input, rest);
+                    Some(ParseContext { line, column, label })
+                }
+                _ => None,
+            })
+            .collect();
+        parse_error
+    }
+
+    /// Unwraps the result of a nom parser run over a slice of `input`,
+    /// rejecting anything the parser left unconsumed
+    pub fn finish_nom<O>(input: &str, result: NomResult<'_, O>) -> Result<O, ParseError> {
+        match result {
+            Ok(("", output)) => Ok(output),
+            Ok((rest, _)) => {
+                let found = rest.chars().next().unwrap_or_default();
+                Err(ParseError::at(input, rest, format!("unexpected trailing {found:?}")))
+            }
+            Err(error) => Err(ParseError::from_nom(input, error)),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Solves one day's puzzle. The parsed state may borrow from the input, so
+/// an executor can't outlive the input it parsed.
+pub trait Executor<'a> {
+    /// Called before `parse`. Only days whose puzzles use different parameters
+    /// for the sample input need to care.
+    fn set_input_kind(&mut self, _kind: InputKind) {}
+
+    fn parse(&mut self, input: &'a str) -> Result<(), ParseError>;
+
+    /// Parts only read the parsed state, so either one can run on its own, in
+    /// any order, and any number of times
+    fn part_one(&self) -> Answer;
+    fn part_two(&self) -> Answer;
+
+    /// Days that are still stubs return false so multi-day runs can skip them
+    fn is_implemented(&self) -> bool {
+        true
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+}
+
+/// The year the harness was first written for, used when no `--year` is given
+pub const DEFAULT_YEAR: u16 = 2023;
+
+/// Builds a fresh executor for one day's puzzle, ready to parse any input
+/// that outlives it. The marker only names the lifetime, since a function
+/// pointer can't be generic over one that only its return type mentions.
+pub type Constructor = for<'a> fn(PhantomData<&'a str>) -> Box<dyn Executor<'a> + 'a>;
+
+/// Parses `input` with `executor` and solves both parts
+pub fn solve_parsed<'a>(executor: &mut dyn Executor<'a>, input: &'a str) -> Result<(Answer, Answer), ParseError> {
+    executor.parse(input)?;
+    Ok((executor.part_one(), executor.part_two()))
+}
+
+/// Declares each day's module and adds its executor to the registry, so a
+/// new day is a single line here
+macro_rules! register_executors {
+    ($($year:literal { $($day:literal => $module:ident::$executor:ident,)* })*) => {
+        $($(pub mod $module;)*)*
+
+        /// A module per day with functions that solve it directly, which the
+        /// library exports as `aoc_2023::dayN`
+        pub mod solvers {
+            $($(
+                pub mod $module {
+                    use crate::executors::{Answer, ParseError};
+
+                    /// Solves both parts of the puzzle. Panics if the input doesn't parse.
+                    pub fn solve(input: &str) -> (Answer, Answer) {
+                        try_solve(input).unwrap_or_else(|error| panic!("Unable to parse the input, {error}"))
+                    }
+
+                    /// Solves both parts of the puzzle, or says where the input doesn't parse.
+                    /// The input is normalized first the same way the CLI does.
+                    pub fn try_solve(input: &str) -> Result<(Answer, Answer), ParseError> {
+                        let input = crate::execution_driver::normalize_input(input);
+                        let mut executor = crate::executors::$module::$executor::default();
+                        crate::executors::solve_parsed(&mut executor, &input)
+                    }
+                }
+            )*)*
+        }
+
+        /// Every executor, keyed by the year and day of the puzzle it solves.
+        /// Days from every year share `crate::utils`.
+        const REGISTRY: &[(u16, u8, Constructor)] = &[
+            $($(($year, $day, {
+                fn construct<'a>(_: PhantomData<&'a str>) -> Box<dyn Executor<'a> + 'a> {
+                    Box::<$module::$executor>::default()
+                }
+                construct
+            }),)*)*
+        ];
+    };
+}
+
+register_executors! {
+    2023 {
+        1 => day1::Day1,
+        2 => day2::Day2,
+        3 => day3::Day3,
+        4 => day4::Day4,
+        5 => day5::Day5,
+        6 => day6::Day6,
+        7 => day7::Day7,
+        8 => day8::Day8,
+        9 => day9::Day9,
+        10 => day10::Day10,
+        11 => day11::Day11,
+        12 => day12::Day12,
+        13 => day13::Day13,
+        14 => day14::Day14,
+        15 => day15::Day15,
+        16 => day16::Day16,
+        17 => day17::Day17,
+        18 => day18::Day18,
+        19 => day19::Day19,
+        20 => day20::Day20,
+        21 => day21::Day21,
+        22 => day22::Day22,
+        23 => day23::Day23,
+        24 => day24::Day24,
+        25 => day25::Day25,
+    }
+}
+
+const _: () = check_registry(REGISTRY);
+
+/// Fails the build if a year registers a day twice, skips a day, or lists
+/// its days out of order
+const fn check_registry(registry: &[(u16, u8, Constructor)]) {
+    let mut i = 0;
+    while i < registry.len() {
+        let (year, day) = (registry[i].0, registry[i].1);
+        assert!(day >= 1 && day <= 25, "registered day is outside 1..=25");
+
+        let mut previous_day = 0;
+        let mut j = 0;
+        while j < i {
+            if registry[j].0 == year {
+                assert!(registry[j].1 != day, "day registered more than once");
+                previous_day = registry[j].1;
+            }
+            j += 1;
+        }
+        assert!(day == previous_day + 1, "days must be registered in order with none missing");
+        i += 1;
+    }
+}
+
+pub fn get_executor<'a>(year: u16, day: u8) -> Option<Box<dyn Executor<'a> + 'a>> {
+    REGISTRY
+        .iter()
+        .find(|&&(y, d, _)| (y, d) == (year, day))
+        .map(|(_, _, construct)| construct(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_examples(day: u8) {
+        let examples = get_executor(DEFAULT_YEAR, day).unwrap().examples();
+        assert!(!examples.is_empty(), "Day {day} has no examples");
+        for (i, example) in examples.iter().enumerate() {
+            let mut executor = get_executor(DEFAULT_YEAR, day).unwrap();
+            executor.set_input_kind(InputKind::Example);
+            executor.parse(example.input).unwrap();
+
+            // Parts only run when the example covers them since samples for
+            // one part aren't always valid inputs for the other. Part two runs
+            // first and every part runs twice to check that parts don't
+            // depend on each other or on earlier runs.
+            let example_number = i + 1;
+            for (part, expected) in [("two", example.part_two), ("one", example.part_one)] {
+                let Some(expected) = expected else { continue };
+                for _ in 0..2 {
+                    let answer = match part {
+                        "one" => executor.part_one(),
+                        _ => executor.part_two(),
+                    };
+                    assert!(answer.matches(expected), "Day {day} example {example_number} part {part}: expected {expected}, got {answer}");
+                }
+            }
+        }
+    }
+
+    macro_rules! example_tests {
+        ($($name:ident => $day:literal,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    check_examples($day);
+                }
+            )*
+        };
+    }
+
+    example_tests! {
+        day1_examples => 1,
+        day2_examples => 2,
+        day3_examples => 3,
+        day4_examples => 4,
+        day5_examples => 5,
+        day6_examples => 6,
+        day7_examples => 7,
+        day8_examples => 8,
+        day9_examples => 9,
+        day10_examples => 10,
+        day11_examples => 11,
+        day12_examples => 12,
+        day13_examples => 13,
+        day14_examples => 14,
+        day15_examples => 15,
+        day16_examples => 16,
+        day17_examples => 17,
+        day18_examples => 18,
+        day19_examples => 19,
+        day20_examples => 20,
+        day21_examples => 21,
+    }
+
+    #[test]
+    fn parallel_days_answer_the_same_on_any_thread_count() {
+        let solve = |day: u8, threads: usize| {
+            let pool = crate::execution_driver::thread_pool(Some(threads)).unwrap();
+            pool.install(|| {
+                let mut executor = get_executor(DEFAULT_YEAR, day).unwrap();
+                executor.set_input_kind(InputKind::Example);
+                executor.parse(executor.examples()[0].input).unwrap();
+                (executor.part_one(), executor.part_two())
+            })
+        };
+        for day in [11, 12, 16] {
+            assert_eq!(solve(day, 1), solve(day, 4), "Day {day}");
+        }
+    }
+
+    #[test]
+    fn days_can_be_solved_without_the_registry() {
+        let example = &get_executor(DEFAULT_YEAR, 7).unwrap().examples()[0];
+        let (part_one, part_two) = solvers::day7::solve(&example.input.replace('\n', "\r\n"));
+        assert!(part_one.matches(example.part_one.unwrap()));
+        assert!(part_two.matches(example.part_two.unwrap()));
+
+        let error = solvers::day2::try_solve("Game 1: 3 blue, 4 red\nGame 2: 1 bl").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+    }
+
+    fn parse_error(day: u8, input: &str) -> ParseError {
+        let mut executor = get_executor(DEFAULT_YEAR, day).unwrap();
+        executor.parse(input).expect_err("input should not parse")
+    }
+
+    #[test]
+    fn grid_parse_errors_point_at_the_character() {
+        let error = parse_error(10, ".....\n.S-\r.\n.|.|.\n");
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.message, "unexpected character '\\r'");
+    }
+
+    #[test]
+    fn ragged_and_empty_grids_are_rejected() {
+        // The last line of a sample cut off partway through
+        let error = parse_error(14, "O....#\nO.OO#.\n..#");
+        assert_eq!((error.line, error.column, error.message.as_str()), (3, 4, "line is shorter than the first"));
+        let error = parse_error(13, "#.#\n.#.\n\n##\n#.#\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (5, 3, "line is longer than the first"));
+        for day in [3, 10, 11, 13, 14, 16, 17, 21] {
+            assert_eq!(parse_error(day, "").message, "expected a grid", "Day {day}");
+        }
+        assert_eq!(parse_error(21, "...\n.#.\n").message, "no start tile");
+        // As wide in characters, but not in bytes
+        let error = parse_error(3, "1\u{e9}.\n...\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 2, "unexpected character '\u{e9}'"));
+    }
+
+    #[test]
+    fn inputs_too_large_for_fixed_storage_are_rejected() {
+        let error = parse_error(20, "broadcaster -> a, b, c, d, e, f, g, h, i\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 40, "too many outputs"));
+        let error = parse_error(20, &"%a -> b\n".repeat(65));
+        assert_eq!((error.line, error.message.as_str()), (65, "more than 64 modules"));
+
+        let wide = format!("{}\n", ".".repeat(129));
+        let error = parse_error(14, &wide);
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 129, "grid is wider than 128 columns"));
+        let error = parse_error(16, &".\n".repeat(129));
+        assert_eq!((error.line, error.message.as_str()), (129, "grid is taller than 128 rows"));
+        let error = parse_error(17, &"1\n".repeat(257));
+        assert_eq!((error.line, error.message.as_str()), (257, "grid is taller than 256 rows"));
+    }
+
+    #[test]
+    fn day_6_rejects_a_fifth_race_where_it_is() {
+        let error = parse_error(6, "Time: 1 2 3 4 5\nDistance: 1 2 3 4\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 14, "more than four races"));
+        let labels: Vec<_> = error.context.iter().map(|c| c.label).collect();
+        assert_eq!(labels, ["race times"]);
+    }
+
+    #[test]
+    fn malformed_steps_are_rejected() {
+        let error = parse_error(15, "rn=1,cm-,qp\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 12, "expected '-' or '=' after the label"));
+        let error = parse_error(15, "rn=1,ab=x\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 9, "expected a focal length from 1 to 9"));
+        assert_eq!(parse_error(15, "rn=1,,cm-\n").column, 6);
+        assert_eq!(parse_error(15, "rn=12\n").message, "expected ',' after the step");
+    }
+
+    #[test]
+    fn lines_without_digits_are_rejected() {
+        let error = parse_error(1, "1abc2\nx\n");
+        assert_eq!((error.line, error.column, error.message.as_str()), (2, 1, "line has no digit"));
+    }
+
+    #[test]
+    fn nom_parse_errors_point_at_the_remaining_input() {
+        // A download cut off partway through the second line
+        let error = parse_error(2, "Game 1: 3 blue, 4 red\nGame 2: 1 bl");
+        assert_eq!((error.line, error.column), (2, 11));
+        let labels: Vec<_> = error.context.iter().map(|c| (c.label, c.column)).collect();
+        assert_eq!(labels, [("cube color", 11), ("sample", 9)]);
+
+        let error = parse_error(19, "in{x>10:qq,A}\n\n{x=1,m=2,a=3,s=4}\n");
+        assert_eq!((error.line, error.column), (1, 9));
+        assert_eq!(error.message, "unknown workflow");
+
+        let error = parse_error(18, "R 6 (#70c710)\nD 5 (#0dc571)  \n");
+        assert_eq!((error.line, error.column), (2, 14));
+        assert_eq!(error.message, "unexpected trailing ' '");
+    }
+
+    #[test]
+    fn day_18_colors_label_what_is_wrong() {
+        let labels = |input| {
+            let error = parse_error(18, input);
+            error.context.iter().map(|c| (c.label, c.column)).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels("R 6 (#70c7)\n"),
+            [("five hex digits of distance", 7), ("color", 5)]
+        );
+        assert_eq!(
+            labels("R 6 (#70c714)\n"),
+            [("direction digit", 12), ("color", 5)]
+        );
+    }
+}
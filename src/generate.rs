@@ -0,0 +1,843 @@
+//! Random puzzle inputs for stress and scaling tests. Every generator is
+//! deterministic in its seed, and at scale 1 writes an input about the size
+//! of a real one. Larger scales grow whatever the day's input is made of,
+//! such as the grid, the number of records or the number of modules, without
+//! regard for limits the executors assume, since finding those is the point.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// SplitMix64, which is tiny and gives the same sequence on every platform
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+
+    /// True `percent` times out of a hundred
+    pub fn percent(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+type Generator = fn(&mut Rng, usize) -> String;
+
+/// Writes an input for the day from the seed, or fails for days that have
+/// no generator
+pub fn generate(day: u8, seed: u64, scale: usize) -> Result<String, String> {
+    let generator: Generator = match day {
+        1 => day1,
+        2 => day2,
+        3 => day3,
+        4 => day4,
+        5 => day5,
+        6 => day6,
+        7 => day7,
+        8 => day8,
+        9 => day9,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        19 => day19,
+        20 => day20,
+        21 => day21,
+        _ => return Err(format!("Day {day} has no input generator")),
+    };
+    Ok(generator(&mut Rng::new(seed), scale))
+}
+
+fn digit(rng: &mut Rng) -> char {
+    char::from(b'1' + rng.below(9) as u8)
+}
+
+fn letter(rng: &mut Rng) -> char {
+    char::from(b'a' + rng.below(26) as u8)
+}
+
+/// A square grid where each cell is picked by `cell`
+fn grid(size: usize, mut cell: impl FnMut(usize, usize) -> char) -> Vec<Vec<char>> {
+    (0..size)
+        .map(|i| (0..size).map(|j| cell(i, j)).collect())
+        .collect()
+}
+
+fn grid_to_string(grid: &[Vec<char>]) -> String {
+    let mut out = String::new();
+    for row in grid {
+        out.extend(row);
+        out.push('\n');
+    }
+    out
+}
+
+/// Distinct labels made of `alphabet`, at least `min_length` long, growing
+/// longer when there aren't enough short ones
+fn labels(rng: &mut Rng, count: usize, min_length: usize, alphabet: &[u8]) -> Vec<String> {
+    let mut length = min_length;
+    while alphabet.len().pow(length as u32) < count * 2 {
+        length += 1;
+    }
+    let mut labels = BTreeSet::new();
+    let mut ordered = vec![];
+    while ordered.len() < count {
+        let label: String = (0..length)
+            .map(|_| char::from(*rng.pick(alphabet)))
+            .collect();
+        if labels.insert(label.clone()) {
+            ordered.push(label);
+        }
+    }
+    ordered
+}
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// Lines of letters with at least one digit, some digits spelled out
+fn day1(rng: &mut Rng, scale: usize) -> String {
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut out = String::new();
+    for _ in 0..1000 * scale {
+        let length = rng.range(3..=12);
+        let digit_at = rng.below(length);
+        for k in 0..length {
+            match rng.below(6) {
+                _ if k == digit_at => out.push(digit(rng)),
+                0 | 1 => out.push_str(WORDS[rng.below(WORDS.len())]),
+                2 => out.push(digit(rng)),
+                _ => out.push(letter(rng)),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn day2(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for game in 1..=100 * scale {
+        let mut samples = vec![];
+        for _ in 0..rng.range(1..=6) {
+            let mut colors = ["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            let cubes: Vec<_> = colors[..rng.range(1..=3)]
+                .iter()
+                .map(|color| format!("{} {color}", rng.range(1..=20)))
+                .collect();
+            samples.push(cubes.join(", "));
+        }
+        _ = writeln!(out, "Game {game}: {}", samples.join("; "));
+    }
+    out
+}
+
+/// Numbers scattered over a grid with symbols between them, where no number
+/// touches more than one symbol
+fn day3(rng: &mut Rng, scale: usize) -> String {
+    const SYMBOLS: &[char] = &['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+    let size = 140 * scale;
+    let mut cells = vec![vec!['.'; size]; size];
+    let mut numbers = vec![vec![None; size]; size];
+    let mut number_count = 0;
+    for i in 0..size {
+        let mut j = rng.below(4);
+        while j + 3 <= size {
+            let length = rng.range(1..=3);
+            cells[i][j] = digit(rng);
+            for k in 1..length {
+                cells[i][j + k] = char::from(b'0' + rng.below(10) as u8);
+            }
+            numbers[i][j..j + length].fill(Some(number_count));
+            number_count += 1;
+            j += length + rng.range(1..=8);
+        }
+    }
+
+    let mut has_symbol = vec![false; number_count];
+    for i in 0..size {
+        for j in 0..size {
+            if numbers[i][j].is_some() || !rng.percent(6) {
+                continue;
+            }
+            let mut neighbors: Vec<usize> = vec![];
+            for row in &numbers[i.saturating_sub(1)..(i + 2).min(size)] {
+                neighbors.extend(row[j.saturating_sub(1)..(j + 2).min(size)].iter().flatten());
+            }
+            if neighbors.iter().any(|&n| has_symbol[n]) {
+                continue;
+            }
+            for n in neighbors {
+                has_symbol[n] = true;
+            }
+            cells[i][j] = *rng.pick(SYMBOLS);
+        }
+    }
+    grid_to_string(&cells)
+}
+
+fn join_numbers(numbers: &[usize]) -> String {
+    let numbers: Vec<_> = numbers.iter().map(|n| format!("{n:>2}")).collect();
+    numbers.join(" ")
+}
+
+/// Cards rarely win more than a couple of others, so copies stay countable
+fn day4(rng: &mut Rng, scale: usize) -> String {
+    let cards = 200 * scale;
+    let width = cards.to_string().len();
+    let mut out = String::new();
+    for card in 1..=cards {
+        let matches = (*rng.pick(&[0, 0, 0, 0, 1, 1, 2, 3])).min(cards - card);
+        let mut numbers: Vec<usize> = (1..=99).collect();
+        rng.shuffle(&mut numbers);
+        let winning = &numbers[..10];
+        let mut have: Vec<_> = winning[..matches]
+            .iter()
+            .chain(&numbers[10..35 - matches])
+            .copied()
+            .collect();
+        rng.shuffle(&mut have);
+        _ = writeln!(
+            out,
+            "Card {card:>width$}: {} | {}",
+            join_numbers(winning),
+            join_numbers(&have)
+        );
+    }
+    out
+}
+
+/// Each map shuffles consecutive slices of the numbers below 2^32 into a new
+/// order, so it's a bijection like the real ones
+fn day5(rng: &mut Rng, scale: usize) -> String {
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    const LIMIT: u64 = 1 << 32;
+    let seeds: Vec<_> = (0..10 * scale)
+        .map(|_| {
+            let start = rng.next_u64() % (LIMIT / 2);
+            let length = 1 + rng.next_u64() % (LIMIT / 20);
+            format!("{start} {length}")
+        })
+        .collect();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+
+    for categories in CATEGORIES.windows(2) {
+        let mut bounds: Vec<u64> = (0..=30 * scale).map(|_| rng.next_u64() % LIMIT).collect();
+        bounds.sort_unstable();
+        bounds.dedup();
+        let mut slices: Vec<_> = bounds.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        rng.shuffle(&mut slices);
+        let mut destination = bounds[0];
+        let mut mappings = vec![];
+        for (source, length) in slices {
+            mappings.push(format!("{destination} {source} {length}"));
+            destination += length;
+        }
+        rng.shuffle(&mut mappings);
+        _ = write!(
+            out,
+            "\n{}-to-{} map:\n{}\n",
+            categories[0],
+            categories[1],
+            mappings.join("\n")
+        );
+    }
+    out
+}
+
+/// Four races, as many as there are in a real input, whose records can always
+/// be beaten. Larger scales make the races longer rather than adding more.
+fn day6(rng: &mut Rng, scale: usize) -> String {
+    let mut times = String::from("Time:    ");
+    let mut distances = String::from("Distance:");
+    for _ in 0..4 {
+        let time = rng.range(40..=99) * scale;
+        let record = rng.range(time * time / 8..=time * time / 4 - 1);
+        _ = write!(times, " {time:>4}");
+        _ = write!(distances, " {record:>4}");
+    }
+    format!("{times}\n{distances}\n")
+}
+
+fn day7(rng: &mut Rng, scale: usize) -> String {
+    const CARDS: &[u8] = b"23456789TJQKA";
+    let mut out = String::new();
+    for _ in 0..1000 * scale {
+        let hand: String = (0..5).map(|_| char::from(*rng.pick(CARDS))).collect();
+        _ = writeln!(out, "{hand} {}", rng.range(1..=1000));
+    }
+    out
+}
+
+/// Six ghosts, each on a ladder of node pairs from its `A` node to its `Z`
+/// node and back round, so every path reaches its `Z` node in a fixed number
+/// of steps whichever way it turns
+fn day8(rng: &mut Rng, scale: usize) -> String {
+    const ALPHANUMERIC: &[u8] = b"0123456789BCDEFGHIJKLMNOPQRSTUVWXY";
+    let directions: String = (0..rng.range(250..=300))
+        .map(|_| if rng.percent(50) { 'L' } else { 'R' })
+        .collect();
+
+    let ghosts = 6;
+    let lengths: Vec<_> = (0..ghosts).map(|_| rng.range(40..=70) * scale).collect();
+    let pair_count: usize = lengths.iter().map(|length| 2 * length).sum();
+    let mut names = labels(rng, pair_count, 3, ALPHANUMERIC).into_iter();
+    let prefixes = labels(rng, ghosts, 2, ALPHANUMERIC);
+
+    let mut nodes = vec![];
+    for (ghost, &length) in lengths.iter().enumerate() {
+        let (start, end) = match ghost {
+            0 => ("AAA".to_owned(), "ZZZ".to_owned()),
+            _ => (
+                format!("{}A", prefixes[ghost]),
+                format!("{}Z", prefixes[ghost]),
+            ),
+        };
+        let rungs: Vec<_> = (1..length)
+            .map(|_| (names.next().unwrap(), names.next().unwrap()))
+            .collect();
+        nodes.push(format!("{start} = ({}, {})", rungs[0].0, rungs[0].1));
+        for k in 0..rungs.len() {
+            let (left, right) = match rungs.get(k + 1) {
+                Some((left, right)) => (left, right),
+                None => (&end, &end),
+            };
+            nodes.push(format!("{} = ({left}, {right})", rungs[k].0));
+            nodes.push(format!("{} = ({left}, {right})", rungs[k].1));
+        }
+        nodes.push(format!("{end} = ({}, {})", rungs[0].0, rungs[0].1));
+    }
+    rng.shuffle(&mut nodes);
+    format!("{directions}\n\n{}\n", nodes.join("\n"))
+}
+
+/// Polynomial sequences, built up from constant differences
+fn day9(rng: &mut Rng, scale: usize) -> String {
+    const LENGTH: usize = 21;
+    let mut out = String::new();
+    for _ in 0..200 * scale {
+        let degree = rng.range(1..=6);
+        let mut sequence = vec![rng.range(0..=10) as i64 - 5; LENGTH - degree];
+        for _ in 0..degree {
+            let mut value = rng.range(0..=40) as i64 - 20;
+            let mut next = vec![value];
+            for difference in sequence {
+                value += difference;
+                next.push(value);
+            }
+            sequence = next;
+        }
+        let values: Vec<_> = sequence.iter().map(i64::to_string).collect();
+        _ = writeln!(out, "{}", values.join(" "));
+    }
+    out
+}
+
+/// A pipe loop around a skyline of random plateaus, among random pipes that
+/// aren't part of it
+fn day10(rng: &mut Rng, scale: usize) -> String {
+    let size = 140 * scale;
+    let (left, right, bottom) = (1, size - 2, size - 2);
+
+    // Plateaus are at least two columns wide so the climb into one and the
+    // drop out of it happen in different columns
+    let mut plateaus = vec![];
+    let mut column = left;
+    while column <= right {
+        let mut width = rng.range(2..=6);
+        if right + 1 - column < width + 2 {
+            width = right + 1 - column;
+        }
+        plateaus.push((column, column + width - 1, rng.range(1..=bottom - 1)));
+        column += width;
+    }
+
+    let mut path: Vec<(usize, usize)> = (left..=right).map(|j| (bottom, j)).collect();
+    let (_, _, last_height) = plateaus[plateaus.len() - 1];
+    path.extend((last_height..bottom).rev().map(|i| (i, right)));
+    let mut row = last_height;
+    for k in (0..plateaus.len()).rev() {
+        let (first, last, _) = plateaus[k];
+        path.extend((first..last).rev().map(|j| (row, j)));
+        if k == 0 {
+            path.extend((row + 1..bottom).map(|i| (i, left)));
+            break;
+        }
+        let (_, next_last, next_height) = plateaus[k - 1];
+        if next_height > row {
+            path.extend((row + 1..=next_height).map(|i| (i, first)));
+            path.push((next_height, next_last));
+        } else {
+            path.push((row, next_last));
+            path.extend((next_height..row).rev().map(|i| (i, next_last)));
+        }
+        row = next_height;
+    }
+
+    let mut cells = grid(size, |_, _| {
+        if rng.percent(40) {
+            '.'
+        } else {
+            *rng.pick(&['|', '-', 'L', 'J', '7', 'F'])
+        }
+    });
+    for k in 0..path.len() {
+        let (i, j) = path[k];
+        let towards = |(ni, nj): (usize, usize)| match (ni.cmp(&i), nj.cmp(&j)) {
+            (std::cmp::Ordering::Less, _) => 'N',
+            (std::cmp::Ordering::Greater, _) => 'S',
+            (_, std::cmp::Ordering::Greater) => 'E',
+            _ => 'W',
+        };
+        let previous = towards(path[(k + path.len() - 1) % path.len()]);
+        let next = towards(path[(k + 1) % path.len()]);
+        cells[i][j] = match (previous.min(next), previous.max(next)) {
+            ('N', 'S') => '|',
+            ('E', 'W') => '-',
+            ('E', 'N') => 'L',
+            ('N', 'W') => 'J',
+            ('S', 'W') => '7',
+            _ => 'F',
+        };
+    }
+
+    // Nothing off the loop may look like it connects to the start
+    let (si, sj) = *rng.pick(&path);
+    for (ni, nj) in [(si - 1, sj), (si + 1, sj), (si, sj - 1), (si, sj + 1)] {
+        if !path.contains(&(ni, nj)) {
+            cells[ni][nj] = '.';
+        }
+    }
+    cells[si][sj] = 'S';
+    grid_to_string(&cells)
+}
+
+/// Sparse galaxies with some rows and columns left empty to expand
+fn day11(rng: &mut Rng, scale: usize) -> String {
+    let size = 140 * scale;
+    let empty_rows: Vec<_> = (0..size).map(|_| rng.percent(5)).collect();
+    let empty_columns: Vec<_> = (0..size).map(|_| rng.percent(5)).collect();
+    let cells = grid(size, |i, j| {
+        if !empty_rows[i] && !empty_columns[j] && rng.percent(3) {
+            '#'
+        } else {
+            '.'
+        }
+    });
+    grid_to_string(&cells)
+}
+
+/// Short rows with a handful of groups, since rows are unfolded five times
+fn day12(rng: &mut Rng, scale: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..1000 * scale {
+        let length = rng.range(4..=20);
+        let mut springs = vec!['.'; length];
+        let mut groups = vec![];
+        let mut position = rng.below(3);
+        while position < length && groups.len() < 6 {
+            let group = rng.range(1..=(length - position).min(5));
+            springs[position..position + group].fill('#');
+            groups.push(group.to_string());
+            position += group + rng.range(1..=3);
+        }
+        if groups.is_empty() {
+            springs[0] = '#';
+            groups.push("1".to_owned());
+        }
+        for spring in springs.iter_mut() {
+            if rng.percent(40) {
+                *spring = '?';
+            }
+        }
+        let springs: String = springs.into_iter().collect();
+        _ = writeln!(out, "{springs} {}", groups.join(","));
+    }
+    out
+}
+
+/// Patterns that reflect perfectly across one line and across another but
+/// for a single smudge
+fn day13(rng: &mut Rng, scale: usize) -> String {
+    let mut patterns = vec![];
+    for _ in 0..100 * scale {
+        let (height, width) = (rng.range(7..=17), rng.range(7..=17));
+        let mirror_row = rng.range(1..=(height - 1) / 2);
+        let mirror_column = rng.range(1..=width - 1);
+        let column_span = mirror_column.min(width - mirror_column);
+
+        // Rows below the mirror row are random but symmetric across the
+        // mirror column, and the rows above reflect them
+        let mut rows = vec![vec!['.'; width]; height];
+        for row in &mut rows[mirror_row..] {
+            for j in 0..width {
+                let reflected = (2 * mirror_column).checked_sub(j + 1);
+                row[j] = match reflected {
+                    Some(r) if r < j && j < mirror_column + column_span => row[r],
+                    _ if rng.percent(50) => '#',
+                    _ => '.',
+                };
+            }
+        }
+        for k in 0..mirror_row {
+            rows[mirror_row - 1 - k] = rows[mirror_row + k].clone();
+        }
+        // Outside the rows the mirror row reflects, so only the mirror
+        // column is smudged
+        let i = rng.range(2 * mirror_row..=height - 1);
+        let j = rng.range(mirror_column - column_span..=mirror_column + column_span - 1);
+        rows[i][j] = if rows[i][j] == '#' { '.' } else { '#' };
+
+        if rng.percent(50) {
+            rows.reverse();
+        }
+        if rng.percent(50) {
+            rows = (0..width)
+                .map(|j| rows.iter().map(|row| row[j]).collect())
+                .collect();
+        }
+        patterns.push(grid_to_string(&rows));
+    }
+    patterns.join("\n")
+}
+
+fn day14(rng: &mut Rng, scale: usize) -> String {
+    let cells = grid(100 * scale, |_, _| match rng.below(100) {
+        0..=19 => 'O',
+        20..=34 => '#',
+        _ => '.',
+    });
+    grid_to_string(&cells)
+}
+
+/// Steps over a pool of labels small enough that lenses get replaced and
+/// removed
+fn day15(rng: &mut Rng, scale: usize) -> String {
+    let pool: Vec<String> = (0..500 * scale)
+        .map(|_| (0..rng.range(2..=6)).map(|_| letter(rng)).collect())
+        .collect();
+    let steps: Vec<_> = (0..4000 * scale)
+        .map(|_| {
+            let label = rng.pick(&pool);
+            if rng.percent(60) {
+                format!("{label}={}", digit(rng))
+            } else {
+                format!("{label}-")
+            }
+        })
+        .collect();
+    format!("{}\n", steps.join(","))
+}
+
+fn day16(rng: &mut Rng, scale: usize) -> String {
+    let cells = grid(110 * scale, |_, _| {
+        if rng.percent(90) {
+            '.'
+        } else {
+            *rng.pick(&['/', '\\', '|', '-'])
+        }
+    });
+    grid_to_string(&cells)
+}
+
+fn day17(rng: &mut Rng, scale: usize) -> String {
+    grid_to_string(&grid(141 * scale, |_, _| digit(rng)))
+}
+
+/// Splits steps longer than `limit` into several in the same direction
+fn split_steps(steps: Vec<(usize, usize)>, limit: usize) -> Vec<(usize, usize)> {
+    let mut out = vec![];
+    for (direction, mut length) in steps {
+        while length > limit {
+            out.push((direction, limit));
+            length -= limit;
+        }
+        out.push((direction, length));
+    }
+    out
+}
+
+/// A skyline of plateaus traced clockwise from its bottom left corner, as
+/// `(direction, length)` steps where directions count clockwise from right
+fn skyline(
+    rng: &mut Rng,
+    plateaus: usize,
+    widths: RangeInclusive<usize>,
+    heights: RangeInclusive<usize>,
+) -> Vec<(usize, usize)> {
+    const RIGHT: usize = 0;
+    const DOWN: usize = 1;
+    const LEFT: usize = 2;
+    const UP: usize = 3;
+    let mut steps = vec![];
+    let mut height = rng.range(heights.clone());
+    let mut total_width = 0;
+    steps.push((UP, height));
+    for k in 0..plateaus {
+        if k > 0 {
+            let next = loop {
+                let next = rng.range(heights.clone());
+                if next != height {
+                    break next;
+                }
+            };
+            if next > height {
+                steps.push((UP, next - height));
+            } else {
+                steps.push((DOWN, height - next));
+            }
+            height = next;
+        }
+        let width = rng.range(widths.clone());
+        steps.push((RIGHT, width));
+        total_width += width;
+    }
+    steps.push((DOWN, height));
+    steps.push((LEFT, total_width));
+    steps
+}
+
+/// Two unrelated trenches, one in the directions and one in the colors,
+/// padded with extra steps so both have as many
+fn day18(rng: &mut Rng, scale: usize) -> String {
+    let plateaus = 175 * scale;
+    let mut small = split_steps(skyline(rng, plateaus, 1..=6, 1..=250), 255);
+    let mut large = split_steps(skyline(rng, plateaus, 1..=4000, 1..=0xfffff), 0xfffff);
+    while small.len() != large.len() {
+        let shorter = if small.len() < large.len() {
+            &mut small
+        } else {
+            &mut large
+        };
+        let (k, &(direction, length)) = shorter
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, (_, length))| *length)
+            .unwrap();
+        let first = rng.range(1..=length - 1);
+        shorter[k] = (direction, first);
+        shorter.insert(k + 1, (direction, length - first));
+    }
+
+    let mut out = String::new();
+    for ((direction, length), (color_direction, color_length)) in small.into_iter().zip(large) {
+        let direction = char::from(b"RDLU"[direction]);
+        _ = writeln!(
+            out,
+            "{direction} {length} (#{color_length:05x}{color_direction})"
+        );
+    }
+    out
+}
+
+/// A tree of workflows rooted at `in`, so no part loops forever
+fn day19(rng: &mut Rng, scale: usize) -> String {
+    let count = 550 * scale;
+    let mut names = labels(rng, count, 3, LOWERCASE);
+    names[0] = "in".to_owned();
+
+    let mut workflows = vec![];
+    let mut next_child = 1;
+    for i in 0..count {
+        let rules = rng.range(1..=3);
+        let mut destinations = vec![];
+        let mut branched = false;
+        for d in 0..=rules {
+            // The last workflow made so far has to carry on the tree
+            let must_branch = d == rules && next_child == i + 1 && !branched;
+            if next_child < count && (must_branch || rng.percent(55)) {
+                destinations.push(names[next_child].clone());
+                next_child += 1;
+                branched = true;
+            } else {
+                destinations.push(rng.pick(&["A", "R"]).to_string());
+            }
+        }
+        let fallback = destinations.pop().unwrap();
+        let rules: Vec<_> = destinations
+            .into_iter()
+            .map(|destination| {
+                let category = char::from(*rng.pick(b"xmas"));
+                let comparison = *rng.pick(&['<', '>']);
+                format!(
+                    "{category}{comparison}{}:{destination}",
+                    rng.range(2..=3999)
+                )
+            })
+            .collect();
+        workflows.push(format!("{}{{{},{fallback}}}", names[i], rules.join(",")));
+    }
+    rng.shuffle(&mut workflows);
+
+    let parts: Vec<_> = (0..200 * scale)
+        .map(|_| {
+            let [x, m, a, s] = [(); 4].map(|_| rng.range(1..=4000));
+            format!("{{x={x},m={m},a={a},s={s}}}")
+        })
+        .collect();
+    format!("{}\n\n{}\n", workflows.join("\n"), parts.join("\n"))
+}
+
+/// Binary counters of twelve flip-flops, each reset by a conjunction when it
+/// reaches its own number, which then pulses through an inverter into the
+/// conjunction in front of `rx`
+fn day20(rng: &mut Rng, scale: usize) -> String {
+    const BITS: usize = 12;
+    const MAX_OUTPUTS: usize = 8;
+    let counters = 4 * scale;
+    let mut relays = 0;
+    let mut fan_out = counters;
+    while fan_out > MAX_OUTPUTS {
+        fan_out = fan_out.div_ceil(MAX_OUTPUTS);
+        relays += 2 * fan_out;
+    }
+    let mut names = labels(rng, counters * (BITS + 2) + relays + 3, 2, LOWERCASE).into_iter();
+    let mut next_name = || loop {
+        let name = names.next().unwrap();
+        if name != "rx" {
+            break name;
+        }
+    };
+
+    let collector = next_name();
+    let mut modules = vec![format!("&{collector} -> rx")];
+    let mut starts = vec![];
+    for _ in 0..counters {
+        let target = loop {
+            let target = rng.range(1 << (BITS - 1)..=(1 << BITS) - 1) | 1;
+            // Each zero bit is another output of the conjunction, which
+            // can't have more than eight
+            if target.count_ones() >= 6 {
+                break target;
+            }
+        };
+        let flip_flops: Vec<_> = (0..BITS).map(|_| next_name()).collect();
+        let conjunction = next_name();
+        let inverter = next_name();
+
+        let mut conjunction_outputs = vec![flip_flops[0].clone()];
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut outputs = vec![];
+            if let Some(next) = flip_flops.get(bit + 1) {
+                outputs.push(next.clone());
+            }
+            if target & (1 << bit) != 0 {
+                outputs.push(conjunction.clone());
+            } else {
+                conjunction_outputs.push(flip_flop.clone());
+            }
+            rng.shuffle(&mut outputs);
+            modules.push(format!("%{flip_flop} -> {}", outputs.join(", ")));
+        }
+        conjunction_outputs.push(inverter.clone());
+        rng.shuffle(&mut conjunction_outputs);
+        modules.push(format!(
+            "&{conjunction} -> {}",
+            conjunction_outputs.join(", ")
+        ));
+        modules.push(format!("&{inverter} -> {collector}"));
+        starts.push(flip_flops[0].clone());
+    }
+    // Modules can't have more than eight outputs, so the button's pulse
+    // reaches larger numbers of counters through pairs of single input
+    // conjunctions, which invert it twice and so pass it on unchanged
+    while starts.len() > MAX_OUTPUTS {
+        starts = starts
+            .chunks(MAX_OUTPUTS)
+            .map(|chunk| {
+                let (first, second) = (next_name(), next_name());
+                modules.push(format!("&{first} -> {second}"));
+                modules.push(format!("&{second} -> {}", chunk.join(", ")));
+                first
+            })
+            .collect();
+    }
+    modules.push(format!("broadcaster -> {}", starts.join(", ")));
+    rng.shuffle(&mut modules);
+    format!("{}\n", modules.join("\n"))
+}
+
+/// A square garden with an odd side, the start in the middle and clear
+/// lanes through it and around the edge
+fn day21(rng: &mut Rng, scale: usize) -> String {
+    let size = 130 * scale + 1;
+    let middle = size / 2;
+    let mut cells = grid(size, |i, j| {
+        let clear =
+            i == middle || j == middle || i == 0 || j == 0 || i == size - 1 || j == size - 1;
+        if !clear && rng.percent(12) {
+            '#'
+        } else {
+            '.'
+        }
+    });
+    cells[middle][middle] = 'S';
+    grid_to_string(&cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_driver::run_day;
+    use crate::executors::{self, DEFAULT_YEAR};
+
+    #[test]
+    fn same_seed_same_input() {
+        assert_eq!(generate(8, 7, 1), generate(8, 7, 1));
+        assert_ne!(generate(8, 7, 1), generate(8, 8, 1));
+    }
+
+    #[test]
+    fn every_generated_input_parses_and_solves() {
+        for day in 1..=21 {
+            let input = generate(day, 2023, 1).unwrap();
+            let executor = executors::get_executor(DEFAULT_YEAR, day).unwrap();
+            let result = run_day(executor, &input, Default::default());
+            assert!(result.is_ok(), "Day {day}: {:?}", result.err());
+        }
+    }
+}
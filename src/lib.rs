@@ -0,0 +1,26 @@
+//! Solvers for Advent of Code and the harness that fetches, runs, times and
+//! submits them. Each day can be solved directly, as in
+//! `aoc_2023::day17::solve(input)`, without going through the executor
+//! registry or the CLI.
+
+pub mod alloc_metrics;
+pub mod answers;
+pub mod bench_history;
+pub mod benchmark;
+pub mod execution_driver;
+pub mod executors;
+pub mod generate;
+pub mod new_day;
+pub mod puzzle_page;
+pub mod report;
+pub mod setup_day;
+pub mod submit;
+pub mod utils;
+pub mod verify;
+pub mod watch;
+
+pub use executors::solvers::*;
+pub use executors::{Answer, ParseError};
+pub use utils::direction::Direction;
+pub use utils::partitioned_by::PartitionedBy;
+pub use utils::point::Point;
@@ -1,10 +1,15 @@
-pub mod execution_driver;
-pub mod executors;
-pub mod setup_day;
-pub mod utils;
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
 
-use clap::{Parser, Subcommand};
-use setup_day::setup_day;
+use aoc_2023::execution_driver::{self, InputSource, Parts};
+use aoc_2023::executors::DEFAULT_YEAR;
+use aoc_2023::report::OutputFormat;
+use aoc_2023::{bench_history, benchmark, generate, new_day, setup_day, submit, verify, watch};
+use clap::{Args, Parser, Subcommand};
+
+const NUM_DAYS: u8 = 25;
 
 #[derive(Parser)]
 struct Cli {
@@ -14,19 +19,353 @@ struct Cli {
 
 #[derive(Subcommand, Debug)]
 enum Command {
-    Fetch { day: u8 },
-    Execute { day: u8 },
+    /// Download puzzle inputs into `inputs/<year>/`
+    Fetch {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// The year of the puzzles
+        #[arg(long, default_value_t = DEFAULT_YEAR, value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+        /// Also save the examples and their answers from the puzzle page
+        #[arg(long)]
+        examples: bool,
+        /// Overwrite inputs that have already been downloaded
+        #[arg(long)]
+        force: bool,
+    },
+    Execute {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// The year of the puzzles
+        #[arg(long, default_value_t = DEFAULT_YEAR, value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+        /// Print results as text, or as JSON or CSV for other tooling
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// Run against the Nth example from the puzzle description instead of
+        /// the real input
+        #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+        example: Option<usize>,
+        /// Read the input from a file instead of `inputs/`, or from stdin with `-`
+        #[arg(long, value_name = "PATH", conflicts_with = "example")]
+        input: Option<String>,
+        /// Run only this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Run the days that solve in parallel on this many threads instead of
+        /// one per core
+        #[arg(long, value_name = "N")]
+        threads: Option<NonZeroUsize>,
+        /// Run everything on one thread, the same as `--threads 1`
+        #[arg(long, conflicts_with = "threads")]
+        sequential: bool,
+        /// Keep running, and execute the day again every time its input file
+        /// is saved
+        #[arg(long, conflicts_with = "format")]
+        watch: bool,
+    },
+    /// Time repeated runs of each day and report statistics per phase
+    Bench {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// The year of the puzzles
+        #[arg(long, default_value_t = DEFAULT_YEAR, value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+        /// Untimed iterations to run before measuring
+        #[arg(long, default_value_t = 3)]
+        warmup: u32,
+        /// Measured iterations per day
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+        /// Thread counts to compare, such as `1,2,4`. Defaults to one thread
+        /// and one per core.
+        #[arg(long, value_name = "N", value_delimiter = ',')]
+        threads: Vec<NonZeroUsize>,
+        /// Only measure on one thread
+        #[arg(long, conflicts_with = "threads")]
+        sequential: bool,
+        /// Measure this file instead of the puzzle input, such as one written
+        /// by `generate`
+        #[arg(long, value_name = "PATH")]
+        input: Option<PathBuf>,
+        /// Append the results to the benchmark history, keyed by the current
+        /// commit and the input's hash
+        #[arg(long)]
+        save: bool,
+        /// Flag every phase that got slower than it was at this commit, or
+        /// any other name git has for it
+        #[arg(long, value_name = "BASELINE")]
+        compare: Option<String>,
+        /// How many percent slower than the baseline a phase may get
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            default_value_t = 10.0,
+            requires = "compare"
+        )]
+        threshold: f64,
+        /// A file of per-day time budgets to hold the medians measured on the
+        /// default number of threads to
+        #[arg(long, value_name = "PATH", default_value = bench_history::BUDGETS_PATH)]
+        budgets: String,
+    },
+    /// Run a part against the puzzle input and submit its answer
+    Submit {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=NUM_DAYS as i64))]
+        day: u8,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// The year of the puzzles
+        #[arg(long, default_value_t = DEFAULT_YEAR, value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+    },
+    /// Check answers against the recorded answers file
+    Verify {
+        #[command(flatten)]
+        selection: DaySelection,
+        /// The year of the puzzles
+        #[arg(long, default_value_t = DEFAULT_YEAR, value_parser = clap::value_parser!(u16).range(2015..))]
+        year: u16,
+        /// Record the current answers instead of checking them
+        #[arg(long)]
+        record: bool,
+    },
+    /// Write a random input for a day, for stress and scaling tests
+    Generate {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=NUM_DAYS as i64))]
+        day: u8,
+        /// The same seed always gives the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// How large an input to write, where 1 is about the size of a real one
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        scale: u32,
+        /// Write the input to this file instead of stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Generate a stub executor for a day and register it
+    NewDay {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=NUM_DAYS as i64))]
+        day: u8,
+    },
+}
+
+#[derive(Args, Debug)]
+struct DaySelection {
+    /// A single day, or an inclusive range of days such as `3..=12`
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    days: Option<Days>,
+    /// Run every day
+    #[arg(long)]
+    all: bool,
+}
+
+impl DaySelection {
+    fn days(&self) -> Days {
+        match &self.days {
+            Some(days) => days.clone(),
+            None => Days::Range(1..=NUM_DAYS),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Days {
+    Single(u8),
+    Range(RangeInclusive<u8>),
+}
+
+impl Days {
+    fn range(self) -> RangeInclusive<u8> {
+        match self {
+            Days::Single(day) => day..=day,
+            Days::Range(range) => range,
+        }
+    }
+}
+
+impl FromStr for Days {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |raw: &str| match raw.trim().parse::<u8>() {
+            Ok(day @ 1..=NUM_DAYS) => Ok(day),
+            _ => Err(format!("`{raw}` is not a day between 1 and {NUM_DAYS}")),
+        };
+
+        let days = if let Some((start, end)) = s.split_once("..=") {
+            Days::Range(parse_day(start)?..=parse_day(end)?)
+        } else if let Some((start, end)) = s.split_once("..") {
+            Days::Range(parse_day(start)?..=parse_day(end)? - 1)
+        } else {
+            Days::Single(parse_day(s)?)
+        };
+
+        match &days {
+            Days::Range(range) if range.is_empty() => Err(format!("`{s}` contains no days")),
+            _ => Ok(days),
+        }
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
     match cli.command {
-        Command::Fetch { day } => {
-            setup_day(day);
+        Command::Fetch {
+            selection,
+            year,
+            examples,
+            force,
+        } => match setup_day::fetch(year, selection.days().range(), examples, force) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        },
+        Command::Execute {
+            selection,
+            year,
+            format,
+            example,
+            input,
+            part,
+            threads,
+            sequential,
+            watch,
+        } => {
+            let parts = Parts::from_arg(part);
+            let source = match (example, input) {
+                (Some(n), _) => InputSource::Example(n),
+                (_, Some(path)) => InputSource::from_arg(&path),
+                _ => InputSource::Puzzle,
+            };
+            let threads = if sequential {
+                Some(1)
+            } else {
+                threads.map(NonZeroUsize::get)
+            };
+            let pool = match execution_driver::thread_pool(threads) {
+                Ok(pool) => pool,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(2);
+                }
+            };
+            pool.install(|| match (selection.days(), &source) {
+                (Days::Single(day), _) if watch => {
+                    if let Err(e) = watch::watch(year, day, &source, parts) {
+                        eprintln!("{e}");
+                        std::process::exit(2);
+                    }
+                }
+                (Days::Range(_), _) if watch => {
+                    eprintln!("--watch can only be used when executing a single day");
+                    std::process::exit(2);
+                }
+                (Days::Single(day), _) => {
+                    if let Err(e) = execution_driver::execute(year, day, &source, parts, format) {
+                        eprintln!("{e}");
+                        std::process::exit(1);
+                    }
+                }
+                (Days::Range(_), InputSource::File(_) | InputSource::Stdin) => {
+                    eprintln!("--input can only be used when executing a single day");
+                    std::process::exit(2);
+                }
+                (Days::Range(range), _) => {
+                    execution_driver::execute_days(year, range, &source, parts, format)
+                }
+            })
+        }
+        Command::Bench {
+            selection,
+            year,
+            warmup,
+            iterations,
+            threads,
+            sequential,
+            input,
+            save,
+            compare,
+            threshold,
+            budgets,
+        } => {
+            if input.is_some() && matches!(selection.days(), Days::Range(_)) {
+                eprintln!("--input can only be used when benchmarking a single day");
+                std::process::exit(2);
+            }
+            let thread_counts = match (sequential, threads.is_empty()) {
+                (true, _) => vec![1],
+                (false, true) => benchmark::default_thread_counts(),
+                (false, false) => threads.into_iter().map(NonZeroUsize::get).collect(),
+            };
+            let options = benchmark::BenchOptions {
+                warmup_iterations: warmup,
+                iterations,
+                thread_counts,
+                input,
+                save,
+                baseline: compare,
+                threshold,
+                budgets_path: budgets,
+            };
+            match benchmark::bench(year, selection.days().range(), options) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(2);
+                }
+            }
+        }
+        Command::Submit { day, part, year } => match submit::submit(year, day, part) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        },
+        Command::Verify {
+            selection,
+            year,
+            record,
+        } => match verify::verify(year, selection.days().range(), record) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        },
+        Command::Generate {
+            day,
+            seed,
+            scale,
+            output,
+        } => {
+            let written =
+                generate::generate(day, seed, scale as usize).and_then(|input| match output {
+                    Some(path) => std::fs::write(&path, input)
+                        .map_err(|e| format!("Unable to write {}: {e}", path.display())),
+                    None => {
+                        print!("{input}");
+                        Ok(())
+                    }
+                });
+            if let Err(e) = written {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
         }
-        Command::Execute { day } => {
-            let executors = executors::get_executors();
-            execution_driver::execute(executors, day);
+        Command::NewDay { day } => {
+            if let Err(e) = new_day::new_day(day) {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
         }
     }
 }
@@ -0,0 +1,209 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::executors::DEFAULT_YEAR;
+
+pub const EXECUTORS_DIR: &str = "src/executors";
+
+/// The stub a new day starts from. It reports itself as unimplemented so
+/// multi-day runs skip it until the parts are written.
+fn template(day: u8) -> String {
+    format!(
+        r#"use super::{{Answer, Example, Executor, ParseError}};
+
+#[derive(Default)]
+pub struct Day{day};
+
+const EXAMPLES: [Example; 1] = [Example {{
+    input: "\
+",
+    part_one: None,
+    part_two: None,
+}}];
+
+impl Executor<'_> for Day{day} {{
+    fn parse(&mut self, _input: &str) -> Result<(), ParseError> {{
+        Ok(())
+    }}
+
+    fn part_one(&self) -> Answer {{
+        Answer::NotImplemented
+    }}
+
+    fn part_two(&self) -> Answer {{
+        Answer::NotImplemented
+    }}
+
+    fn is_implemented(&self) -> bool {{
+        false
+    }}
+
+    fn examples(&self) -> &'static [Example] {{
+        &EXAMPLES
+    }}
+}}
+"#
+    )
+}
+
+/// An executor is still a stub for as long as it says it isn't implemented
+fn is_stub(source: &str) -> bool {
+    let compact: String = source.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.contains("fnis_implemented(&self)->bool{false}")
+}
+
+/// Adds `line` to the block opened by the line `block`, keeping the block's
+/// entries ordered by day. Returns `None` when the day already has an entry.
+fn insert_entry(
+    source: &str,
+    block: &str,
+    day: u8,
+    line: &str,
+    entry_day: fn(&str) -> Option<u8>,
+) -> Result<Option<String>, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim() == block)
+        .ok_or_else(|| format!("Unable to find `{block}` in executors/mod.rs"))?;
+
+    let mut insert_at = start + 1;
+    for (i, existing) in lines.iter().enumerate().skip(start + 1) {
+        match entry_day(existing.trim()) {
+            Some(d) if d == day => return Ok(None),
+            Some(d) if d < day => insert_at = i + 1,
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    let mut updated: Vec<&str> = lines;
+    updated.insert(insert_at, line);
+    Ok(Some(updated.join("\n") + "\n"))
+}
+
+fn registry_entry_day(line: &str) -> Option<u8> {
+    line.split_once(" => ")?.0.parse().ok()
+}
+
+fn example_test_entry_day(line: &str) -> Option<u8> {
+    line.split_once(" => ")?
+        .1
+        .trim_end_matches(',')
+        .parse()
+        .ok()
+}
+
+/// Registers the day's executor and its example test in executors/mod.rs.
+/// Days have to be registered in order, so the previous day must already be.
+fn register(mod_source: &str, day: u8) -> Result<String, String> {
+    let mut source = mod_source.to_owned();
+    let previous_registered = source
+        .lines()
+        .any(|l| registry_entry_day(l.trim()) == Some(day - 1));
+    if day > 1 && !previous_registered {
+        return Err(format!(
+            "Day {} has to be registered before day {day}",
+            day - 1
+        ));
+    }
+
+    let entries = [
+        (
+            format!("{DEFAULT_YEAR} {{"),
+            format!("        {day} => day{day}::Day{day},"),
+            registry_entry_day as fn(&str) -> Option<u8>,
+        ),
+        (
+            "example_tests! {".to_owned(),
+            format!("        day{day}_examples => {day},"),
+            example_test_entry_day,
+        ),
+    ];
+    for (block, line, entry_day) in entries {
+        if let Some(updated) = insert_entry(&source, &block, day, &line, entry_day)? {
+            source = updated;
+        }
+    }
+    Ok(source)
+}
+
+/// Writes a stub executor for the day and registers it. A day that already
+/// has real code is left alone.
+pub fn new_day(day: u8) -> Result<(), String> {
+    let executors_dir = Path::new(EXECUTORS_DIR);
+    let mod_path = executors_dir.join("mod.rs");
+    let mod_source = fs::read_to_string(&mod_path).map_err(|e| {
+        format!(
+            "Unable to read {}: {e}. Run new-day from the root of the repository.",
+            mod_path.display()
+        )
+    })?;
+
+    let day_path = executors_dir.join(format!("day{day}.rs"));
+    match fs::read_to_string(&day_path) {
+        Ok(existing) if !is_stub(&existing) => {
+            return Err(format!(
+                "{} already has an implementation, refusing to overwrite it",
+                day_path.display()
+            ))
+        }
+        Ok(_) => println!("Replacing the stub in {}", day_path.display()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Unable to read {}: {e}", day_path.display())),
+    }
+
+    let updated_mod = register(&mod_source, day)?;
+    fs::write(&day_path, template(day))
+        .map_err(|e| format!("Unable to write {}: {e}", day_path.display()))?;
+    println!("Created {}", day_path.display());
+    if updated_mod != mod_source {
+        fs::write(&mod_path, updated_mod)
+            .map_err(|e| format!("Unable to write {}: {e}", mod_path.display()))?;
+        println!("Registered day {day} in {}", mod_path.display());
+    }
+    println!(
+        "Paste the example into EXAMPLES and remove `is_implemented` once both parts are solved"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD_SOURCE: &str = "\
+register_executors! {
+    2023 {
+        1 => day1::Day1,
+        2 => day2::Day2,
+    }
+}
+
+    example_tests! {
+        day1_examples => 1,
+    }
+";
+
+    #[test]
+    fn registers_the_executor_and_its_example_test() {
+        let updated = register(MOD_SOURCE, 3).unwrap();
+        assert!(updated.contains("        2 => day2::Day2,\n        3 => day3::Day3,\n    }"));
+        assert!(updated.contains("        day1_examples => 1,\n        day3_examples => 3,\n    }"));
+        assert_eq!(register(&updated, 3).unwrap(), updated);
+    }
+
+    #[test]
+    fn days_are_registered_in_order() {
+        let updated = register(MOD_SOURCE, 2).unwrap();
+        assert!(updated.contains("        day1_examples => 1,\n        day2_examples => 2,\n"));
+        assert!(register(MOD_SOURCE, 4).is_err());
+    }
+
+    #[test]
+    fn only_stubs_are_overwritten() {
+        assert!(is_stub(&template(7)));
+        assert!(!is_stub(&template(7).replace("false", "true")));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::answers::Answers;
+
+const EXAMPLE_TABLE_PREFIX: &str = "example_";
+
+/// An example input lifted from a puzzle description, along with whichever
+/// answers the description gives for it
+#[derive(Debug, Default, PartialEq)]
+pub struct ExampleFixture {
+    pub input: String,
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+/// Pulls the examples out of a day's puzzle page.
+///
+/// Each part of the puzzle is its own `<article>`. The first `<pre><code>`
+/// block in a part is taken as its example, and the last emphasized
+/// `<code><em>` in the part as the answer for that example. Part two usually
+/// reuses part one's example, so its answer is attached to the first example
+/// unless part two brings a block of its own.
+pub fn extract_examples(html: &str) -> Vec<ExampleFixture> {
+    let mut fixtures: Vec<ExampleFixture> = Vec::new();
+    for (part, article) in html.split("<article").skip(1).take(2).enumerate() {
+        let article = article.split("</article>").next().unwrap_or_default();
+        let answer = last_emphasized_code(article);
+        let index = match first_code_block(article) {
+            Some(input) => match fixtures.iter().position(|f| f.input == input) {
+                Some(index) => index,
+                None => {
+                    fixtures.push(ExampleFixture {
+                        input,
+                        ..Default::default()
+                    });
+                    fixtures.len() - 1
+                }
+            },
+            None if fixtures.is_empty() => continue,
+            None => 0,
+        };
+        let fixture = &mut fixtures[index];
+        match part {
+            0 => fixture.part_one = answer,
+            _ => fixture.part_two = answer,
+        }
+    }
+    fixtures
+}
+
+fn first_code_block(article: &str) -> Option<String> {
+    let start = article.find("<pre><code>")? + "<pre><code>".len();
+    let end = start + article[start..].find("</code></pre>")?;
+    Some(decode_text(&article[start..end]))
+}
+
+fn last_emphasized_code(article: &str) -> Option<String> {
+    let start = article.rfind("<code><em>")? + "<code><em>".len();
+    let end = start + article[start..].find("</em></code>")?;
+    Some(decode_text(&article[start..end]))
+}
+
+/// Drops any markup nested in the text and decodes the entities the puzzle
+/// pages use
+fn decode_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn example_input_path(inputs_dir: &Path, day: u8, example: usize) -> PathBuf {
+    inputs_dir.join(format!("day_{day}_example_{example}"))
+}
+
+pub fn example_answers_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    inputs_dir.join(format!("day_{day}_examples.toml"))
+}
+
+/// Writes each example's input to `day_N_example_K` and their answers to
+/// `day_N_examples.toml`, alongside the day's puzzle input
+pub fn save_examples(
+    inputs_dir: &Path,
+    day: u8,
+    fixtures: &[ExampleFixture],
+) -> Result<(), String> {
+    fs::create_dir_all(inputs_dir)
+        .map_err(|e| format!("Unable to create {}: {e}", inputs_dir.display()))?;
+
+    let mut answers = Answers::default();
+    for (i, fixture) in fixtures.iter().enumerate() {
+        let path = example_input_path(inputs_dir, day, i + 1);
+        fs::write(&path, &fixture.input)
+            .map_err(|e| format!("Unable to write {}: {e}", path.display()))?;
+        for (part, answer) in [(1, &fixture.part_one), (2, &fixture.part_two)] {
+            if let Some(answer) = answer {
+                answers.record(i as u8 + 1, part, answer);
+            }
+        }
+    }
+
+    let path = example_answers_path(inputs_dir, day);
+    answers
+        .save_tables(&path.to_string_lossy(), EXAMPLE_TABLE_PREFIX)
+        .map_err(|e| format!("Unable to write {}: {e}", path.display()))
+}
+
+/// Reads back the input of a saved example
+pub fn load_example_input(inputs_dir: &Path, day: u8, example: usize) -> Option<String> {
+    fs::read_to_string(example_input_path(inputs_dir, day, example)).ok()
+}
+
+/// Reads back the answers recorded for a day's saved examples
+pub fn load_example_answers(inputs_dir: &Path, day: u8) -> Result<Answers, String> {
+    let path = example_answers_path(inputs_dir, day);
+    Answers::load_tables(&path.to_string_lossy(), EXAMPLE_TABLE_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 0: Fixture ---</h2>
+<p>For example:</p>
+<pre><code>1 &lt; 2
+3 &amp; <em>4</em>
+</code></pre>
+<p>Here, the <code>&lt;</code> lines add up to <code><em>10</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now multiply instead, which gives <code><em>24</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn extracts_example_and_answers() {
+        assert_eq!(
+            extract_examples(PAGE),
+            vec![ExampleFixture {
+                input: "1 < 2\n3 & 4\n".to_owned(),
+                part_one: Some("10".to_owned()),
+                part_two: Some("24".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn part_two_with_its_own_example() {
+        let page = PAGE.replace(
+            "<p>Now multiply",
+            "<pre><code>5\n6\n</code></pre><p>Now multiply",
+        );
+        let fixtures = extract_examples(&page);
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[0].part_two, None);
+        assert_eq!(fixtures[1].input, "5\n6\n");
+        assert_eq!(fixtures[1].part_one, None);
+        assert_eq!(fixtures[1].part_two.as_deref(), Some("24"));
+    }
+
+    #[test]
+    fn saved_examples_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc_2023_examples_{}", std::process::id()));
+        let fixtures = extract_examples(PAGE);
+        save_examples(&dir, 7, &fixtures).unwrap();
+
+        assert_eq!(
+            load_example_input(&dir, 7, 1).as_deref(),
+            Some("1 < 2\n3 & 4\n")
+        );
+        let answers = load_example_answers(&dir, 7).unwrap();
+        assert_eq!(answers.get(1, 1), Some("10"));
+        assert_eq!(answers.get(1, 2), Some("24"));
+        _ = fs::remove_dir_all(dir);
+    }
+}
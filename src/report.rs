@@ -0,0 +1,229 @@
+use clap::ValueEnum;
+
+use crate::alloc_metrics::AllocStats;
+use crate::execution_driver::DayOutcome;
+use crate::executors::Answer;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+const CSV_HEADER: &str =
+    "day,status,part_one,part_two,parse_ns,part_one_ns,part_two_ns,total_ns,threads,input_hash";
+
+struct Record<'a> {
+    day: u8,
+    status: &'a str,
+    part_one: Option<&'a Answer>,
+    part_two: Option<&'a Answer>,
+    timings_ns: Option<[u128; 4]>,
+    allocations: Option<[AllocStats; 4]>,
+    input_hash: Option<String>,
+}
+
+impl<'a> Record<'a> {
+    fn new(day: u8, outcome: &'a DayOutcome) -> Record<'a> {
+        match outcome {
+            DayOutcome::Completed { result, input_hash } => Record {
+                day,
+                status: "completed",
+                part_one: result.p1_answer.as_ref(),
+                part_two: result.p2_answer.as_ref(),
+                timings_ns: Some([
+                    result.parse_time.as_nanos(),
+                    result.p1_time.as_nanos(),
+                    result.p2_time.as_nanos(),
+                    result.total_time().as_nanos(),
+                ]),
+                allocations: result.allocations,
+                input_hash: Some(format!("{input_hash:016x}")),
+            },
+            DayOutcome::ParseFailed(_) => Record {
+                day,
+                status: "parse error",
+                part_one: None,
+                part_two: None,
+                timings_ns: None,
+                allocations: None,
+                input_hash: None,
+            },
+            DayOutcome::Skipped(reason) => Record {
+                day,
+                status: reason,
+                part_one: None,
+                part_two: None,
+                timings_ns: None,
+                allocations: None,
+                input_hash: None,
+            },
+        }
+    }
+}
+
+pub fn print_json(outcomes: &[(u8, DayOutcome)]) {
+    let threads = rayon::current_num_threads();
+    println!("[");
+    for (i, (day, outcome)) in outcomes.iter().enumerate() {
+        let record = Record::new(*day, outcome);
+        let json_string = |v: Option<&str>| v.map_or("null".to_owned(), escape_json);
+        let timing = |i: usize| {
+            record
+                .timings_ns
+                .map_or("null".to_owned(), |t| t[i].to_string())
+        };
+        let separator = if i + 1 < outcomes.len() { "," } else { "" };
+        println!(
+            "  {{\"day\": {}, \"status\": {}, \"part_one\": {}, \"part_two\": {}, \
+             \"parse_ns\": {}, \"part_one_ns\": {}, \"part_two_ns\": {}, \"total_ns\": {}, \
+             \"threads\": {}, \"allocations\": {}, \"input_hash\": {}}}{}",
+            record.day,
+            escape_json(record.status),
+            json_answer(record.part_one),
+            json_answer(record.part_two),
+            timing(0),
+            timing(1),
+            timing(2),
+            timing(3),
+            threads,
+            json_allocations(record.allocations),
+            json_string(record.input_hash.as_deref()),
+            separator,
+        );
+    }
+    println!("]");
+}
+
+pub fn print_csv(outcomes: &[(u8, DayOutcome)]) {
+    let threads = rayon::current_num_threads();
+    println!("{CSV_HEADER}");
+    for (day, outcome) in outcomes {
+        let record = Record::new(*day, outcome);
+        let timings = match record.timings_ns {
+            Some(t) => t.map(|v| v.to_string()),
+            None => Default::default(),
+        };
+        println!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            record.day,
+            escape_csv(record.status),
+            csv_answer(record.part_one),
+            csv_answer(record.part_two),
+            timings[0],
+            timings[1],
+            timings[2],
+            timings[3],
+            threads,
+            record.input_hash.unwrap_or_default(),
+        );
+    }
+}
+
+/// Numbers stay numbers so consumers can compare them as values
+fn json_answer(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(answer @ (Answer::Unsigned(_) | Answer::Signed(_))) => answer.to_string(),
+        Some(Answer::Text(text)) => escape_json(text),
+        Some(Answer::NotImplemented) | None => "null".to_owned(),
+    }
+}
+
+/// `null` unless the build counts allocations
+fn json_allocations(allocations: Option<[AllocStats; 4]>) -> String {
+    let Some(phases) = allocations else {
+        return "null".to_owned();
+    };
+    let fields: Vec<_> = ["parse", "part_one", "part_two", "total"]
+        .iter()
+        .zip(phases)
+        .map(|(phase, stats)| {
+            format!(
+                "\"{phase}\": {{\"count\": {}, \"bytes\": {}, \"peak_bytes\": {}}}",
+                stats.allocations, stats.bytes, stats.peak_bytes
+            )
+        })
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn csv_answer(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(Answer::NotImplemented) | None => String::new(),
+        Some(answer) => escape_csv(&answer.to_string()),
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_escapes_control_characters() {
+        assert_eq!(escape_json("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+
+    #[test]
+    fn json_answers_keep_their_type() {
+        assert_eq!(json_answer(Some(&Answer::Signed(-7))), "-7");
+        assert_eq!(json_answer(Some(&Answer::from("LKN"))), "\"LKN\"");
+        assert_eq!(json_answer(Some(&Answer::NotImplemented)), "null");
+    }
+
+    #[test]
+    fn json_allocations_are_grouped_by_phase() {
+        assert_eq!(json_allocations(None), "null");
+        let stats = |allocations, bytes, peak_bytes| AllocStats {
+            allocations,
+            bytes,
+            peak_bytes,
+        };
+        assert_eq!(
+            json_allocations(Some([
+                stats(1, 16, 16),
+                stats(0, 0, 0),
+                stats(3, 96, 64),
+                stats(4, 112, 80)
+            ])),
+            "{\"parse\": {\"count\": 1, \"bytes\": 16, \"peak_bytes\": 16}, \
+             \"part_one\": {\"count\": 0, \"bytes\": 0, \"peak_bytes\": 0}, \
+             \"part_two\": {\"count\": 3, \"bytes\": 96, \"peak_bytes\": 64}, \
+             \"total\": {\"count\": 4, \"bytes\": 112, \"peak_bytes\": 80}}"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(escape_csv("12345"), "12345");
+        assert_eq!(escape_csv("1,2"), "\"1,2\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,18 +1,344 @@
 use std::env;
+use std::fmt;
 use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::execution_driver::inputs_dir;
+use crate::executors::DEFAULT_YEAR;
+use crate::puzzle_page::{self, ExampleFixture};
 
 const SESSION_COOKIE_ENV_VAR: &str = "AOC_SESSION_COOKIE";
+const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/mitchpowell1/aoc_2023";
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSessionCookie,
+    AlreadyExists(PathBuf),
+    Status {
+        url: String,
+        code: u16,
+        body: String,
+    },
+    Transport(String),
+    Io(PathBuf, io::Error),
+    NoExamples,
+    SaveExamples(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSessionCookie => write!(
+                f,
+                "The `{SESSION_COOKIE_ENV_VAR}` environment variable must be set to fetch \
+                 puzzle data. Copy the `session` cookie from your browser after logging in."
+            ),
+            FetchError::AlreadyExists(path) => write!(
+                f,
+                "{} already exists. Pass --force to overwrite it.",
+                path.display()
+            ),
+            FetchError::Status { url, code, body } => {
+                write!(f, "{url} responded with status {code}")?;
+                match body.lines().next() {
+                    Some(line) if !line.is_empty() => write!(f, ": {line}"),
+                    _ => Ok(()),
+                }
+            }
+            FetchError::Transport(message) => write!(f, "Request failed: {message}"),
+            FetchError::Io(path, e) => write!(f, "Unable to write {}: {e}", path.display()),
+            FetchError::NoExamples => write!(f, "No example blocks found on the puzzle page"),
+            FetchError::SaveExamples(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session_cookie: String,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session_cookie: &str) -> AocClient {
+        AocClient {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session_cookie: session_cookie.to_owned(),
+        }
+    }
+
+    /// Builds a client from `AOC_SESSION_COOKIE`, talking to `AOC_BASE_URL`
+    /// when it's set so tests can point it at a local server
+    pub fn from_env() -> Result<AocClient, FetchError> {
+        let session_cookie = env::var(SESSION_COOKIE_ENV_VAR)
+            .ok()
+            .filter(|c| !c.trim().is_empty())
+            .ok_or(FetchError::MissingSessionCookie)?;
+        let base_url = env::var(BASE_URL_ENV_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned());
+        Ok(AocClient::new(&base_url, session_cookie.trim()))
+    }
+
+    fn get(&self, path: &str) -> Result<String, FetchError> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self.authorize(self.agent.get(&url)).call();
+        read_response(url, response)
+    }
+
+    fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String, FetchError> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self.authorize(self.agent.post(&url)).send_form(form);
+        read_response(url, response)
+    }
+
+    fn authorize(&self, request: ureq::Request) -> ureq::Request {
+        request.set("Cookie", &format!("session={}", self.session_cookie))
+    }
+
+    pub fn fetch_input(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    pub fn fetch_puzzle(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        self.get(&format!("/{year}/day/{day}"))
+    }
+
+    /// Submits an answer and returns the page the server responds with
+    pub fn submit_answer(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<String, FetchError> {
+        self.post_form(
+            &format!("/{year}/day/{day}/answer"),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )
+    }
+}
+
+fn read_response(
+    url: String,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, FetchError> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| FetchError::Transport(format!("{url}: {e}"))),
+        Err(ureq::Error::Status(code, response)) => Err(FetchError::Status {
+            url,
+            code,
+            body: response.into_string().unwrap_or_default(),
+        }),
+        Err(e) => Err(FetchError::Transport(e.to_string())),
+    }
+}
+
+/// Days unlock at midnight US Eastern time (UTC-5) on each of December 1-25
+pub fn is_unlocked(year: u16, day: u8, now: SystemTime) -> bool {
+    let unlock =
+        UNIX_EPOCH + Duration::from_secs(days_since_epoch(year, 12, day) * 86_400 + 5 * 3_600);
+    now >= unlock
+}
+
+/// Days from 1970-01-01 to the given date in the proleptic Gregorian calendar
+fn days_since_epoch(year: u16, month: u8, day: u8) -> u64 {
+    let (year, month, day) = (year as i64, month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era - 719_468) as u64
+}
+
+pub fn setup_day(
+    client: &AocClient,
+    year: u16,
+    day: u8,
+    inputs_dir: &Path,
+    force: bool,
+) -> Result<PathBuf, FetchError> {
+    let path = inputs_dir.join(format!("day_{day}"));
+    // The default year's inputs may still be where they were saved before
+    // inputs were kept by year, which is where `read_input` looks next
+    let legacy_path = inputs_dir
+        .parent()
+        .filter(|_| year == DEFAULT_YEAR)
+        .map(|dir| dir.join(format!("day_{day}")));
+    if let Some(existing) = [Some(&path), legacy_path.as_ref()]
+        .into_iter()
+        .flatten()
+        .find(|p| p.exists())
+        .filter(|_| !force)
+    {
+        return Err(FetchError::AlreadyExists(existing.clone()));
+    }
+
+    let input = client.fetch_input(year, day)?;
+    fs::create_dir_all(inputs_dir).map_err(|e| FetchError::Io(path.clone(), e))?;
+    fs::write(&path, input).map_err(|e| FetchError::Io(path.clone(), e))?;
+    Ok(path)
+}
+
+/// Downloads the puzzle page and saves its examples next to the day's input.
+/// Fetching again once part one is solved picks up part two's answer.
+pub fn setup_examples(
+    client: &AocClient,
+    year: u16,
+    day: u8,
+    inputs_dir: &Path,
+    force: bool,
+) -> Result<Vec<ExampleFixture>, FetchError> {
+    let path = puzzle_page::example_answers_path(inputs_dir, day);
+    if path.exists() && !force {
+        return Err(FetchError::AlreadyExists(path));
+    }
+
+    let fixtures = puzzle_page::extract_examples(&client.fetch_puzzle(year, day)?);
+    if fixtures.is_empty() {
+        return Err(FetchError::NoExamples);
+    }
+    puzzle_page::save_examples(inputs_dir, day, &fixtures).map_err(FetchError::SaveExamples)?;
+    Ok(fixtures)
+}
+
+/// Fetches every unlocked day of `year` in `days`, along with their examples when
+/// `examples` is set. Existing files are skipped unless `force` is set;
+/// returns false if any download failed.
+pub fn fetch(
+    year: u16,
+    days: RangeInclusive<u8>,
+    examples: bool,
+    force: bool,
+) -> Result<bool, FetchError> {
+    let client = AocClient::from_env()?;
+    let dir = inputs_dir(year);
+    let now = SystemTime::now();
+
+    let mut all_fetched = true;
+    for day in days {
+        if !is_unlocked(year, day, now) {
+            println!("Day {day:>2}: SKIPPED (not unlocked yet)");
+            continue;
+        }
+        match setup_day(&client, year, day, &dir, force) {
+            Ok(path) => println!("Day {day:>2}: saved {}", path.display()),
+            Err(e @ FetchError::AlreadyExists(_)) => println!("Day {day:>2}: {e}"),
+            Err(e) => {
+                all_fetched = false;
+                eprintln!("Day {day:>2}: {e}");
+            }
+        }
+        if !examples {
+            continue;
+        }
+        match setup_examples(&client, year, day, &dir, force) {
+            Ok(fixtures) => {
+                for (i, fixture) in fixtures.iter().enumerate() {
+                    let answer = |a: &Option<String>| a.as_deref().unwrap_or("?").to_owned();
+                    println!(
+                        "Day {day:>2}: saved example {} (part one: {}, part two: {})",
+                        i + 1,
+                        answer(&fixture.part_one),
+                        answer(&fixture.part_two),
+                    );
+                }
+            }
+            Err(e @ FetchError::AlreadyExists(_)) => println!("Day {day:>2}: {e}"),
+            Err(e) => {
+                all_fetched = false;
+                eprintln!("Day {day:>2}: {e}");
+            }
+        }
+    }
+    Ok(all_fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::stub_server::stub_server;
+
+    /// A fresh `inputs/2023` directory, within a directory of its own
+    fn temp_inputs_dir(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc_2023_{name}_{}", std::process::id()));
+        _ = fs::remove_dir_all(&root);
+        root.join("2023")
+    }
+
+    #[test]
+    fn fetches_and_writes_input() {
+        let (base_url, requests) = stub_server("200 OK", "1abc2\n");
+        let client = AocClient::new(&base_url, "secret");
+        let dir = temp_inputs_dir("fetch_ok");
+
+        let path = setup_day(&client, 2023, 1, &dir, false).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "1abc2\n");
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2023/day/1/input "));
+        assert!(request.to_lowercase().contains("cookie: session=secret"));
+        _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn error_status_is_not_saved_as_input() {
+        let (base_url, _requests) = stub_server(
+            "400 Bad Request",
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        );
+        let client = AocClient::new(&base_url, "expired");
+        let dir = temp_inputs_dir("fetch_400");
+
+        let err = setup_day(&client, 2023, 3, &dir, false).unwrap_err();
+        assert!(matches!(err, FetchError::Status { code: 400, .. }));
+        assert!(!dir.join("day_3").exists());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_without_force() {
+        let dir = temp_inputs_dir("fetch_exists");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day_2"), "existing").unwrap();
+        // Nothing is listening here, so any request would fail the test
+        let client = AocClient::new("http://127.0.0.1:9", "secret");
+
+        let err = setup_day(&client, 2023, 2, &dir, false).unwrap_err();
+        assert!(matches!(err, FetchError::AlreadyExists(_)));
+        assert_eq!(fs::read_to_string(dir.join("day_2")).unwrap(), "existing");
+        _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_inputs_saved_before_years() {
+        let dir = temp_inputs_dir("fetch_legacy");
+        let root = dir.parent().unwrap();
+        fs::create_dir_all(root).unwrap();
+        fs::write(root.join("day_2"), "existing").unwrap();
+        let client = AocClient::new("http://127.0.0.1:9", "secret");
+
+        let err = setup_day(&client, 2023, 2, &dir, false).unwrap_err();
+        assert!(matches!(err, FetchError::AlreadyExists(path) if path == root.join("day_2")));
+        assert!(!dir.join("day_2").exists());
+        _ = fs::remove_dir_all(root);
+    }
 
-pub fn setup_day(day: u8) {
-    let session_cookie = env::var(SESSION_COOKIE_ENV_VAR)
-        .expect("The `AOC_SESSION_COOKIE_VAR` environment variable must be set to complete setup");
-    let out = std::process::Command::new("curl")
-        .arg("--cookie")
-        .arg(format!("session={session_cookie}"))
-        .arg(format!("https://adventofcode.com/2023/day/{day}/input"))
-        .output()
-        .expect("Couldn't complete request for input")
-        .stdout;
-
-    fs::write(format!("inputs/day_{day}"), out).expect("Unable to write input contents to file");
+    #[test]
+    fn days_unlock_at_midnight_eastern() {
+        // 2023-12-01T05:00:00Z
+        let unlock = UNIX_EPOCH + Duration::from_secs(1_701_406_800);
+        assert!(!is_unlocked(2023, 1, unlock - Duration::from_secs(1)));
+        assert!(is_unlocked(2023, 1, unlock));
+        assert!(!is_unlocked(2023, 2, unlock));
+    }
 }
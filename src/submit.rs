@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+use crate::answers::{RecordedAnswers, ANSWERS_PATH};
+use crate::execution_driver::{run_days, DayOutcome, InputSource, Parts};
+use crate::executors::{Answer, DEFAULT_YEAR};
+use crate::setup_day::AocClient;
+
+pub const WRONG_ANSWERS_PATH: &str = "wrong_answers.txt";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about which way
+    Incorrect,
+    /// Rate limited, with the number of seconds left to wait
+    Wait(u64),
+    AlreadySolved,
+}
+
+impl Verdict {
+    fn as_key(self) -> Option<&'static str> {
+        match self {
+            Verdict::TooHigh => Some("too_high"),
+            Verdict::TooLow => Some("too_low"),
+            Verdict::Incorrect => Some("incorrect"),
+            _ => None,
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Verdict> {
+        match key {
+            "too_high" => Some(Verdict::TooHigh),
+            "too_low" => Some(Verdict::TooLow),
+            "incorrect" => Some(Verdict::Incorrect),
+            _ => None,
+        }
+    }
+}
+
+/// Works out the verdict from the page returned after submitting an answer
+pub fn classify(page: &str) -> Option<Verdict> {
+    if page.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if page.contains("Did you already complete it") {
+        Some(Verdict::AlreadySolved)
+    } else if page.contains("You gave an answer too recently") {
+        Some(Verdict::Wait(wait_seconds(page).unwrap_or_default()))
+    } else if page.contains("That's not the right answer") {
+        if page.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if page.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else {
+            Some(Verdict::Incorrect)
+        }
+    } else {
+        None
+    }
+}
+
+/// Parses the "You have 1m 30s left to wait" part of a rate limit response
+fn wait_seconds(page: &str) -> Option<u64> {
+    let (before, _) = page.split_once(" left to wait")?;
+    let (_, remaining) = before.rsplit_once("You have ")?;
+    remaining.split_whitespace().try_fold(0, |total, unit| {
+        let (value, multiplier) = match unit.strip_suffix('h') {
+            Some(value) => (value, 3600),
+            None => match unit.strip_suffix('m') {
+                Some(value) => (value, 60),
+                None => (unit.strip_suffix('s')?, 1),
+            },
+        };
+        Some(total + value.parse::<u64>().ok()? * multiplier)
+    })
+}
+
+/// Answers the server has already rejected, one `year day part verdict answer`
+/// line per submission. Lines without a year are from before there was more
+/// than one, and are the default year's.
+#[derive(Default, Debug, PartialEq)]
+pub struct WrongAnswers {
+    parts: BTreeMap<(u16, u8, u8), Vec<(Verdict, String)>>,
+}
+
+impl WrongAnswers {
+    pub fn load(path: &str) -> Result<WrongAnswers, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => WrongAnswers::parse(&contents).map_err(|e| format!("{path}: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(WrongAnswers::default()),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<WrongAnswers, String> {
+        let mut wrong_answers = WrongAnswers::default();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Days never take more than two digits, so four are a year
+            let (year, line) = match line.split_once(' ') {
+                Some((year, rest)) if year.len() == 4 => (year.parse(), rest),
+                _ => (Ok(DEFAULT_YEAR), line),
+            };
+            let mut fields = line.splitn(4, ' ');
+            let mut field = || fields.next().unwrap_or_default();
+            let (day, part, verdict, answer) = (field(), field(), field(), field());
+            match (year, day.parse(), part.parse(), Verdict::from_key(verdict)) {
+                (Ok(year), Ok(day), Ok(part), Some(verdict)) if !answer.is_empty() => {
+                    wrong_answers.record(year, day, part, verdict, answer)
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected `year day part verdict answer`",
+                        i + 1
+                    ))
+                }
+            }
+        }
+        Ok(wrong_answers)
+    }
+
+    pub fn record(&mut self, year: u16, day: u8, part: u8, verdict: Verdict, answer: &str) {
+        self.parts
+            .entry((year, day, part))
+            .or_default()
+            .push((verdict, answer.to_owned()));
+    }
+
+    /// Explains why `answer` can't be right for the given part, based on
+    /// earlier rejections
+    pub fn rule_out(&self, year: u16, day: u8, part: u8, answer: &str) -> Option<String> {
+        let rejected = self.parts.get(&(year, day, part))?;
+        if rejected.iter().any(|(_, wrong)| wrong == answer) {
+            return Some(format!("{answer} was already submitted and rejected"));
+        }
+
+        let value = answer.parse::<i128>().ok()?;
+        rejected.iter().find_map(|(verdict, wrong)| {
+            let wrong_value = wrong.parse::<i128>().ok()?;
+            match verdict {
+                Verdict::TooHigh if value >= wrong_value => {
+                    Some(format!("{answer} is not below {wrong}, which was too high"))
+                }
+                Verdict::TooLow if value <= wrong_value => {
+                    Some(format!("{answer} is not above {wrong}, which was too low"))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut out = String::new();
+        for ((year, day, part), rejected) in &self.parts {
+            for (verdict, answer) in rejected {
+                let key = verdict.as_key().unwrap_or("incorrect");
+                _ = writeln!(out, "{year} {day} {part} {key} {answer}");
+            }
+        }
+        fs::write(path, out)
+    }
+}
+
+/// Submits `answer` unless it has already been ruled out, recording it if the
+/// server rejects it
+pub fn submit_answer(
+    client: &AocClient,
+    wrong_answers: &mut WrongAnswers,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<Verdict, String> {
+    if let Some(reason) = wrong_answers.rule_out(year, day, part, answer) {
+        return Err(format!("Not submitting: {reason}"));
+    }
+
+    let page = client
+        .submit_answer(year, day, part, answer)
+        .map_err(|e| e.to_string())?;
+    let verdict = classify(&page)
+        .ok_or_else(|| "Couldn't make sense of the response to the submission".to_owned())?;
+    if verdict.as_key().is_some() {
+        wrong_answers.record(year, day, part, verdict, answer);
+    }
+    Ok(verdict)
+}
+
+/// Runs `part` of `day` of `year` against the puzzle input and submits the
+/// answer. Returns true if the answer was accepted or the part was already
+/// solved.
+pub fn submit(year: u16, day: u8, part: u8) -> Result<bool, String> {
+    let client = AocClient::from_env().map_err(|e| e.to_string())?;
+    let mut outcomes = run_days(
+        year,
+        day..=day,
+        &InputSource::Puzzle,
+        Parts::from_arg(Some(part)),
+    );
+    let result = match outcomes.pop() {
+        Some((_, DayOutcome::Completed { result, .. })) => result,
+        Some((_, DayOutcome::ParseFailed(error))) => {
+            return Err(format!("Unable to parse the input for day {day}, {error}"))
+        }
+        Some((_, DayOutcome::Skipped(reason))) => {
+            return Err(format!("Day {day} can't be submitted: {reason}"))
+        }
+        None => unreachable!("run_days returns an outcome per day"),
+    };
+    let answer = match part {
+        1 => result.p1_answer,
+        _ => result.p2_answer,
+    };
+    let answer = match answer {
+        Some(Answer::NotImplemented) | None => {
+            return Err(format!("Day {day} part {part} is not implemented"))
+        }
+        Some(answer) => answer.to_string(),
+    };
+    let answer = answer.as_str();
+
+    let mut wrong_answers = WrongAnswers::load(WRONG_ANSWERS_PATH)?;
+    let verdict = submit_answer(&client, &mut wrong_answers, year, day, part, answer)?;
+    wrong_answers
+        .save(WRONG_ANSWERS_PATH)
+        .map_err(|e| format!("Unable to write {WRONG_ANSWERS_PATH}: {e}"))?;
+
+    let label = format!("Day {day:>2} part {part}");
+    match verdict {
+        Verdict::Correct => {
+            println!("{label}: CORRECT ({answer})");
+            let mut answers = RecordedAnswers::load(ANSWERS_PATH)?;
+            answers.year_mut(year).record(day, part, answer);
+            answers
+                .save(ANSWERS_PATH)
+                .map_err(|e| format!("Unable to write {ANSWERS_PATH}: {e}"))?;
+        }
+        Verdict::AlreadySolved => println!("{label}: ALREADY SOLVED (not submitted {answer})"),
+        Verdict::TooHigh => println!("{label}: TOO HIGH ({answer})"),
+        Verdict::TooLow => println!("{label}: TOO LOW ({answer})"),
+        Verdict::Incorrect => println!("{label}: INCORRECT ({answer})"),
+        Verdict::Wait(seconds) => println!("{label}: WAIT {seconds}s before submitting again"),
+    }
+    Ok(matches!(verdict, Verdict::Correct | Verdict::AlreadySolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::stub_server::stub_server;
+
+    #[test]
+    fn classifies_responses() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            classify(&page(
+                "That's the right answer!  You are one gold star closer."
+            )),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            classify(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck..."
+            )),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            classify(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            classify(&page("That's not the right answer.  If you're stuck...")),
+            Some(Verdict::Incorrect)
+        );
+        assert_eq!(
+            classify(&page(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 1m 5s left to wait."
+            )),
+            Some(Verdict::Wait(65))
+        );
+        assert_eq!(
+            classify(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Some(Verdict::AlreadySolved)
+        );
+        assert_eq!(classify("<html>Something else</html>"), None);
+    }
+
+    #[test]
+    fn wrong_answers_round_trip() {
+        let mut wrong_answers = WrongAnswers::default();
+        wrong_answers.record(2023, 3, 1, Verdict::TooHigh, "900");
+        wrong_answers.record(2024, 3, 1, Verdict::Incorrect, "abc");
+        let path = std::env::temp_dir().join(format!("aoc_2023_wrong_{}", std::process::id()));
+        let path = path.to_string_lossy();
+        wrong_answers.save(&path).unwrap();
+        assert_eq!(WrongAnswers::load(&path), Ok(wrong_answers));
+        _ = fs::remove_file(&*path);
+    }
+
+    #[test]
+    fn rejected_answers_are_remembered() {
+        let (base_url, requests) = stub_server(
+            "200 OK",
+            "<article><p>That's not the right answer; your answer is too high.</p></article>",
+        );
+        let client = AocClient::new(&base_url, "secret");
+        let mut wrong_answers = WrongAnswers::default();
+
+        let verdict = submit_answer(&client, &mut wrong_answers, 2023, 4, 2, "500").unwrap();
+        assert_eq!(verdict, Verdict::TooHigh);
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2023/day/4/answer "));
+        assert!(request.ends_with("level=2&answer=500"));
+
+        // The stub only answers once, so these must be refused locally
+        for answer in ["500", "501"] {
+            let err = submit_answer(&client, &mut wrong_answers, 2023, 4, 2, answer).unwrap_err();
+            assert!(err.starts_with("Not submitting"), "{err}");
+        }
+    }
+
+    #[test]
+    fn wrong_answers_are_kept_apart_by_year() {
+        let wrong_answers = WrongAnswers::parse("4 2 too_high 500\n2024 4 2 too_low 10\n").unwrap();
+        assert!(wrong_answers.rule_out(2023, 4, 2, "600").is_some());
+        assert!(wrong_answers.rule_out(2023, 4, 2, "5").is_none());
+        assert!(wrong_answers.rule_out(2024, 4, 2, "600").is_none());
+        assert!(wrong_answers.rule_out(2024, 4, 2, "5").is_some());
+    }
+}
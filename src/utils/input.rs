@@ -0,0 +1,83 @@
+//! Helpers for walking an input after `execution_driver::normalize_input`
+//! has given it `\n` line endings and a single trailing newline
+
+use crate::executors::ParseError;
+
+/// The blank-line separated sections of an input, without their trailing
+/// newlines
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    input.trim_end_matches('\n').split("\n\n")
+}
+
+/// The lines of an input or section along with their 0-based index, ready
+/// for `ParseError::in_line`
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate()
+}
+
+/// The rows of a grid that takes up the whole input, checked to be a
+/// rectangle of at least one row
+pub fn grid(input: &str) -> Result<Vec<&str>, ParseError> {
+    grid_rows(input, input)
+}
+
+/// Like `grid`, for days that keep the grid in fixed-size arrays and so can't
+/// take more than `max_rows` rows of `max_columns`
+pub fn grid_within(
+    input: &str,
+    max_rows: usize,
+    max_columns: usize,
+) -> Result<Vec<&str>, ParseError> {
+    let rows = grid(input)?;
+    if rows[0].chars().count() > max_columns {
+        return Err(ParseError::in_line(
+            0,
+            max_columns,
+            format!("grid is wider than {max_columns} columns"),
+        ));
+    }
+    if rows.len() > max_rows {
+        return Err(ParseError::in_line(
+            max_rows,
+            0,
+            format!("grid is taller than {max_rows} rows"),
+        ));
+    }
+    Ok(rows)
+}
+
+/// The rows of each blank-line separated grid in an input, each checked to be
+/// a rectangle of at least one row
+pub fn grids(input: &str) -> Result<Vec<Vec<&str>>, ParseError> {
+    sections(input).map(|s| grid_rows(input, s)).collect()
+}
+
+/// Checks that every row of `grid`, a slice of `input`, is as wide as the
+/// first, pointing errors at where the row falls short or runs over
+fn grid_rows<'a>(input: &str, grid: &'a str) -> Result<Vec<&'a str>, ParseError> {
+    let rows: Vec<&str> = grid.lines().collect();
+    let Some(first) = rows.first().filter(|row| !row.is_empty()) else {
+        return Err(ParseError::at(input, grid, "expected a grid"));
+    };
+    let width = first.chars().count();
+    for row in &rows {
+        match row.char_indices().nth(width) {
+            Some((extra, _)) => {
+                return Err(ParseError::at(
+                    input,
+                    &row[extra..],
+                    "line is longer than the first",
+                ))
+            }
+            None if row.chars().count() < width => {
+                return Err(ParseError::at(
+                    input,
+                    &row[row.len()..],
+                    "line is shorter than the first",
+                ))
+            }
+            None => {}
+        }
+    }
+    Ok(rows)
+}
@@ -1,3 +1,6 @@
 pub mod direction;
+pub mod input;
 pub mod partitioned_by;
 pub mod point;
+#[cfg(test)]
+pub mod stub_server;
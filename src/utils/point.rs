@@ -45,6 +45,13 @@ impl<T> InBounds for Vec<Vec<T>> {
     }
 }
 
+/// Grids whose rows are borrowed straight from the input
+impl InBounds for [&[u8]] {
+    fn is_in_bounds(&self, Point(i, j): Point) -> bool {
+        i >= 0 && j >= 0 && (i as usize) < self.len() && (j as usize) < self[0].len()
+    }
+}
+
 impl SubAssign<(i8, i8)> for Point {
     fn sub_assign(&mut self, other: (i8, i8)) {
         self.0 -= other.0 as i32;
@@ -0,0 +1,45 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+/// Serves a single canned HTTP response on a local port. Returns the base URL
+/// to point a client at, and a receiver for the raw request (request line,
+/// headers and body) the server received.
+pub fn stub_server(status: &str, body: &str) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let status = status.to_owned();
+    let body = body.to_owned();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+            }
+            request.push_str(&line);
+        }
+        let mut request_body = vec![0; content_length];
+        _ = reader.read_exact(&mut request_body);
+        request.push_str("\r\n");
+        request.push_str(&String::from_utf8_lossy(&request_body));
+
+        _ = write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        _ = sender.send(request);
+    });
+    (base_url, receiver)
+}
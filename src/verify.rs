@@ -0,0 +1,87 @@
+use std::ops::RangeInclusive;
+
+use crate::answers::{RecordedAnswers, ANSWERS_PATH};
+use crate::execution_driver::{run_days, DayOutcome, InputSource, Parts};
+use crate::executors::Answer;
+
+#[derive(Debug, PartialEq)]
+enum Verdict<'a> {
+    Pass,
+    Fail { expected: &'a str },
+    Missing,
+}
+
+fn check<'a>(expected: Option<&'a str>, actual: &Answer) -> Verdict<'a> {
+    match expected {
+        Some(expected) if actual.matches(expected) => Verdict::Pass,
+        Some(expected) => Verdict::Fail { expected },
+        None => Verdict::Missing,
+    }
+}
+
+/// Runs the given days of `year` and compares their answers against the
+/// answers file, or overwrites the recorded answers when `record` is set.
+/// Returns false if any part produced an answer that differs from the
+/// recorded one.
+pub fn verify(year: u16, days: RangeInclusive<u8>, record: bool) -> Result<bool, String> {
+    let mut recorded = RecordedAnswers::load(ANSWERS_PATH)?;
+    let answers = recorded.year_mut(year);
+    let outcomes = run_days(year, days, &InputSource::Puzzle, Parts::Both);
+
+    let mut all_passed = true;
+    for (day, outcome) in &outcomes {
+        let result = match outcome {
+            DayOutcome::Completed { result, .. } => result,
+            DayOutcome::ParseFailed(error) => {
+                all_passed = false;
+                println!("Day {day:>2}: FAIL (unable to parse input, {error})");
+                continue;
+            }
+            DayOutcome::Skipped(reason) => {
+                println!("Day {day:>2}: SKIPPED ({reason})");
+                continue;
+            }
+        };
+
+        for (part, actual) in [(1, &result.p1_answer), (2, &result.p2_answer)] {
+            let label = format!("Day {day:>2} part {part}");
+            let Some(actual) = actual.as_ref().filter(|a| **a != Answer::NotImplemented) else {
+                println!("{label}: SKIPPED (not implemented)");
+                continue;
+            };
+            if record {
+                answers.record(*day, part, &actual.to_string());
+                println!("{label}: RECORDED ({actual})");
+                continue;
+            }
+            match check(answers.get(*day, part), actual) {
+                Verdict::Pass => println!("{label}: PASS ({actual})"),
+                Verdict::Fail { expected } => {
+                    all_passed = false;
+                    println!("{label}: FAIL (expected {expected}, got {actual})");
+                }
+                Verdict::Missing => println!("{label}: MISSING (got {actual})"),
+            }
+        }
+    }
+
+    if record {
+        recorded
+            .save(ANSWERS_PATH)
+            .map_err(|e| format!("Unable to write {ANSWERS_PATH}: {e}"))?;
+    }
+    Ok(all_passed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_verdicts() {
+        let answer = Answer::from(42u32);
+        assert_eq!(check(Some("42"), &answer), Verdict::Pass);
+        assert_eq!(check(Some("43"), &answer), Verdict::Fail { expected: "43" });
+        assert_eq!(check(None, &answer), Verdict::Missing);
+    }
+}
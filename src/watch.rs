@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::execution_driver::{
+    describe_parse_error, input_path, inputs_dir, load_input, run_day, DayResult, InputSource,
+    Parts, INPUTS_DIR,
+};
+use crate::executors::{self, Answer, DEFAULT_YEAR};
+use crate::puzzle_page;
+
+/// How often the watched file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The file an input source reads from, if it can change while we watch
+fn watched_path(year: u16, day: u8, source: &InputSource) -> Result<PathBuf, String> {
+    match source {
+        InputSource::Puzzle => {
+            let path = input_path(year, day);
+            let legacy = Path::new(INPUTS_DIR).join(format!("day_{day}"));
+            if year == DEFAULT_YEAR && !path.exists() && legacy.exists() {
+                Ok(legacy)
+            } else {
+                Ok(path)
+            }
+        }
+        InputSource::File(path) => Ok(path.clone()),
+        InputSource::Stdin => Err("--watch needs a file to watch, not stdin".to_owned()),
+        InputSource::Example(n) => {
+            let executor = executors::get_executor(year, day)
+                .ok_or_else(|| format!("No executor is registered for {year} day {day}"))?;
+            if executor.examples().is_empty() {
+                Ok(puzzle_page::example_input_path(&inputs_dir(year), day, *n))
+            } else {
+                Err(format!(
+                    "Day {day}'s examples are built into the executor. Copy example {n} to a \
+                     file and watch it with --input instead."
+                ))
+            }
+        }
+    }
+}
+
+/// What identifies a version of the watched file, or `None` while it's missing
+fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// How much slower (`+`) or faster (`-`) a phase ran than last time
+fn format_delta(current: Duration, previous: Duration) -> String {
+    if current >= previous {
+        format!("+{:?}", current - previous)
+    } else {
+        format!("-{:?}", previous - current)
+    }
+}
+
+fn print_run(result: &DayResult, previous: Option<&DayResult>, parts: Parts) {
+    let time = |label: &str, current: Duration, previous: Option<Duration>| match previous {
+        Some(previous) => println!("{label}: {current:?} ({})", format_delta(current, previous)),
+        None => println!("{label}: {current:?}"),
+    };
+    time(
+        "Parse time",
+        result.parse_time,
+        previous.map(|p| p.parse_time),
+    );
+    if parts.includes(1) {
+        time("Part one time", result.p1_time, previous.map(|p| p.p1_time));
+    }
+    if parts.includes(2) {
+        time("Part two time", result.p2_time, previous.map(|p| p.p2_time));
+    }
+    time(
+        "Total Time",
+        result.total_time(),
+        previous.map(DayResult::total_time),
+    );
+
+    let answer = |label: &str, current: &Option<Answer>, previous: Option<&Option<Answer>>| {
+        let Some(current) = current else { return };
+        match previous {
+            Some(Some(previous)) if previous != current => {
+                println!("{label}: {current} (was {previous})")
+            }
+            _ => println!("{label}: {current}"),
+        }
+    };
+    answer("P1", &result.p1_answer, previous.map(|p| &p.p1_answer));
+    answer("P2", &result.p2_answer, previous.map(|p| &p.p2_answer));
+}
+
+/// Runs the day every time its input file is saved, comparing each run with
+/// the last one that parsed. Only returns if the day can't be watched.
+pub fn watch(year: u16, day: u8, source: &InputSource, parts: Parts) -> Result<(), String> {
+    if executors::get_executor(year, day).is_none() {
+        return Err(format!("No executor is registered for {year} day {day}"));
+    }
+    let path = watched_path(year, day, source)?;
+    println!("Watching {} for changes", path.display());
+
+    // Nothing has been seen before the first look, not even a missing file
+    let mut seen_version = None;
+    let mut previous: Option<DayResult> = None;
+    loop {
+        let version = file_version(&path);
+        if seen_version == Some(version) {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        let changed = matches!(seen_version, Some(Some(_)));
+        seen_version = Some(version);
+        if version.is_none() {
+            println!("{} doesn't exist yet, waiting for it", path.display());
+            continue;
+        }
+
+        if changed {
+            println!("\n--- {} changed, running day {day} again", path.display());
+        }
+        let (input, kind) = match load_input(year, day, source) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("{e}");
+                continue;
+            }
+        };
+        let mut executor = executors::get_executor(year, day).expect("checked before watching");
+        executor.set_input_kind(kind);
+        match run_day(executor, &input, parts) {
+            Ok(result) => {
+                print_run(&result, previous.as_ref(), parts);
+                previous = Some(result);
+            }
+            Err(error) => eprintln!(
+                "Unable to parse the input for day {day}, {}",
+                describe_parse_error(&input, &error)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_are_signed() {
+        let millis = Duration::from_millis;
+        assert_eq!(format_delta(millis(12), millis(10)), "+2ms");
+        assert_eq!(format_delta(millis(10), millis(12)), "-2ms");
+        assert_eq!(format_delta(millis(10), millis(10)), "+0ns");
+    }
+}